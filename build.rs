@@ -0,0 +1,95 @@
+//! Generates the puzzle solution registry by discovering every `src/year_*/day*.rs` module
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR not set");
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR not set");
+    let src_dir = Path::new(&manifest_dir).join("src");
+
+    // cargo watches every file below a directory, so this also catches newly added days
+    println!("cargo:rerun-if-changed=src");
+
+    let mut solutions = Vec::new();
+
+    for year_entry in fs::read_dir(&src_dir).expect("Failed to read src directory") {
+        let year_path = year_entry.expect("Failed to read src entry").path();
+        let year = match parse_numbered_name(&year_path, "year_") {
+            Some(year) if year_path.is_dir() => year,
+            _ => continue,
+        };
+
+        for day_entry in fs::read_dir(&year_path).expect("Failed to read year directory") {
+            let day_path = day_entry.expect("Failed to read year entry").path();
+            let day = match parse_numbered_name(&day_path, "day") {
+                Some(day) if day_path.extension() == Some("rs".as_ref()) => day,
+                _ => continue,
+            };
+
+            let source = fs::read_to_string(&day_path)
+                .unwrap_or_else(|_| panic!("Failed to read {}", day_path.display()));
+            let title = parse_title(&source, day).unwrap_or_else(|| {
+                panic!(
+                    "{} is missing its '//! --- Day {}: <title> ---' header",
+                    day_path.display(),
+                    day
+                )
+            });
+
+            if !source.contains("pub fn parse(") {
+                panic!(
+                    "{} is missing its 'pub fn parse(input: &str)' function",
+                    day_path.display()
+                );
+            }
+
+            for part in 1..=2 {
+                if source.contains(&format!("pub fn part{}(", part)) {
+                    solutions.push((year, day, part, title.clone()));
+                }
+            }
+        }
+    }
+
+    solutions.sort();
+
+    let registry = solutions
+        .iter()
+        .map(|(year, day, part, title)| {
+            format!(
+                "    Solution {{ year: {year}, day: {day}, part: {part}, title: {title:?}, solver: |input| crate::solve_with(input, crate::year_{year}::day{day}::parse, |parsed| crate::year_{year}::day{day}::part{part}(parsed)) }},\n",
+                year = year,
+                day = day,
+                part = part,
+                title = title,
+            )
+        })
+        .collect::<String>();
+
+    fs::write(
+        Path::new(&out_dir).join("solutions.rs"),
+        format!("&[\n{}]\n", registry),
+    )
+    .expect("Failed to write solution registry");
+}
+
+/// Parses names like `year_2019` or `day9.rs` into their number
+fn parse_numbered_name(path: &Path, prefix: &str) -> Option<u32> {
+    path.file_stem()?
+        .to_str()?
+        .strip_prefix(prefix)?
+        .parse()
+        .ok()
+}
+
+/// Parses the puzzle title from a `//! --- Day 9: Sensor Boost ---` header
+fn parse_title(source: &str, day: u32) -> Option<String> {
+    let header = source.lines().next()?;
+    let title = header
+        .strip_prefix(&format!("//! --- Day {}: ", day))?
+        .strip_suffix(" ---")?;
+
+    Some(title.to_string())
+}
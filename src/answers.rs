@@ -0,0 +1,252 @@
+use crate::{input, Answer, Error, Result, Solution};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Overrides the directory known-correct answers are stored in (defaults to `answers`)
+pub const ANSWERS_DIR_ENV: &str = "AOC_ANSWERS_DIR";
+
+/// Known-correct puzzle answers, stored as `answers/<year>/day<N>.toml` files like:
+///
+/// ```toml
+/// part1 = "3412531"
+/// part2 = "5115927"
+/// ```
+#[derive(Debug)]
+pub struct AnswerStore {
+    dir: PathBuf,
+}
+
+impl AnswerStore {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    /// Opens the answer store in `AOC_ANSWERS_DIR`, or `answers` if it isn't set
+    pub fn from_env() -> Self {
+        let dir = env::var_os(ANSWERS_DIR_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("answers"));
+
+        Self::new(&dir)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The location of the answers for a puzzle, e.g. `answers/2019/day9.toml`
+    pub fn path(&self, year: u16, day: u8) -> PathBuf {
+        self.dir
+            .join(year.to_string())
+            .join(format!("day{}.toml", day))
+    }
+
+    /// The known-correct answer for a puzzle part, if one has been recorded
+    pub fn expected(&self, year: u16, day: u8, part: u8) -> Result<Option<String>> {
+        let answers = self.read(year, day)?;
+
+        let expected = match answers.get(&format!("part{}", part)) {
+            None => None,
+            Some(toml::Value::String(answer)) => Some(answer.clone()),
+            Some(toml::Value::Integer(answer)) => Some(answer.to_string()),
+            Some(other) => {
+                return Err(Error::Answers(format!(
+                    "{}: part{} must be a string or an integer, found {}",
+                    self.path(year, day).display(),
+                    part,
+                    other.type_str()
+                )))
+            }
+        };
+
+        Ok(expected)
+    }
+
+    /// Records the known-correct answer for a puzzle part, keeping the answers to the other part
+    pub fn record(&self, year: u16, day: u8, part: u8, answer: &Answer) -> Result<()> {
+        let path = self.path(year, day);
+        let mut answers = self.read(year, day)?;
+
+        answers.insert(
+            format!("part{}", part),
+            toml::Value::String(answer.to_string()),
+        );
+
+        let serialized = toml::to_string(&answers)
+            .map_err(|error| Error::Answers(format!("{}: {}", path.display(), error)))?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|source| Error::Io {
+                context: format!("creating answers directory {}", parent.display()),
+                source,
+            })?;
+        }
+        fs::write(&path, serialized).map_err(|source| Error::Io {
+            context: format!("writing answers to {}", path.display()),
+            source,
+        })
+    }
+
+    /// Runs a solution against its cached input and compares the result with the known-correct answer
+    pub fn verify(&self, solution: &Solution, inputs_dir: &Path) -> Verification {
+        let input_path = input::cache_path_in(inputs_dir, solution.year, solution.day);
+        let input = match fs::read_to_string(&input_path) {
+            Ok(input) => input,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                return Verification::MissingInput
+            }
+            Err(source) => {
+                return Verification::Error(Error::Io {
+                    context: format!("reading puzzle input from {}", input_path.display()),
+                    source,
+                })
+            }
+        };
+
+        let expected = match self.expected(solution.year, solution.day, solution.part) {
+            Ok(expected) => expected,
+            Err(error) => return Verification::Error(error),
+        };
+
+        let answer = match solution.solve(&input) {
+            Ok(answer) => answer,
+            Err(error) => return Verification::Error(error),
+        };
+
+        match expected {
+            None => Verification::MissingAnswer(answer),
+            Some(expected) if expected == answer.to_string() => Verification::Pass,
+            Some(expected) => Verification::Fail { expected, answer },
+        }
+    }
+
+    fn read(&self, year: u16, day: u8) -> Result<toml::value::Table> {
+        let path = self.path(year, day);
+
+        let serialized = match fs::read_to_string(&path) {
+            Ok(serialized) => serialized,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                return Ok(toml::value::Table::new())
+            }
+            Err(source) => {
+                return Err(Error::Io {
+                    context: format!("reading answers from {}", path.display()),
+                    source,
+                })
+            }
+        };
+
+        toml::from_str(&serialized)
+            .map_err(|error| Error::Answers(format!("{}: {}", path.display(), error)))
+    }
+}
+
+/// The outcome of checking a solution against its known-correct answer
+#[derive(Debug)]
+pub enum Verification {
+    /// The solution produced the known-correct answer
+    Pass,
+
+    /// The solution produced a different answer
+    Fail { expected: String, answer: Answer },
+
+    /// There is no cached input to run the solution against
+    MissingInput,
+
+    /// No answer has been recorded for this part yet
+    MissingAnswer(Answer),
+
+    /// The solution (or reading its input or answers) failed
+    Error(Error),
+}
+
+impl fmt::Display for Verification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pass => write!(f, "pass"),
+            Self::Fail { expected, answer } => {
+                write!(f, "FAIL (expected {}, got {})", expected, answer)
+            }
+            Self::MissingInput => write!(f, "missing input"),
+            Self::MissingAnswer(answer) => write!(f, "missing answer (got {})", answer),
+            Self::Error(error) => write!(f, "ERROR ({})", error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("advent-of-code-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_record_and_read_answers() {
+        let dir = temp_dir("record-and-read-answers");
+        let store = AnswerStore::new(&dir);
+
+        assert_eq!(store.expected(2019, 8, 1).unwrap(), None);
+
+        store.record(2019, 8, 1, &Answer::Integer(1560)).unwrap();
+        store
+            .record(2019, 8, 2, &Answer::from(" █ \n█ █\n"))
+            .unwrap();
+
+        assert_eq!(store.expected(2019, 8, 1).unwrap(), Some("1560".into()));
+        assert_eq!(
+            store.expected(2019, 8, 2).unwrap(),
+            Some(" █ \n█ █\n".into())
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_verify() {
+        let answers_dir = temp_dir("verify-answers");
+        let inputs_dir = temp_dir("verify-inputs");
+        let store = AnswerStore::new(&answers_dir);
+
+        let part1 = registry::find("2019::day1::part1").unwrap();
+        let part2 = registry::find("2019::day1::part2").unwrap();
+
+        assert!(matches!(
+            store.verify(part1, &inputs_dir),
+            Verification::MissingInput
+        ));
+
+        let input_path = input::cache_path_in(&inputs_dir, 2019, 1);
+        fs::create_dir_all(input_path.parent().unwrap()).unwrap();
+        fs::write(&input_path, "1969\n100756\n").unwrap();
+
+        assert!(matches!(
+            store.verify(part1, &inputs_dir),
+            Verification::MissingAnswer(Answer::Integer(34237))
+        ));
+
+        fs::create_dir_all(store.path(2019, 1).parent().unwrap()).unwrap();
+        fs::write(store.path(2019, 1), "part1 = 34237\npart2 = \"51313\"\n").unwrap();
+
+        assert!(matches!(
+            store.verify(part1, &inputs_dir),
+            Verification::Pass
+        ));
+        assert!(matches!(
+            store.verify(part2, &inputs_dir),
+            Verification::Fail { .. }
+        ));
+
+        fs::remove_dir_all(&answers_dir).unwrap();
+        fs::remove_dir_all(&inputs_dir).unwrap();
+    }
+}
@@ -0,0 +1,146 @@
+use crate::year_2019::{IntcodeComputer, IntcodeProgram};
+use crate::{Result, Solution};
+use std::time::{Duration, Instant};
+
+/// Every puzzle has a solution that completes in at most 15 seconds on ten-year-old hardware
+pub const TIME_BUDGET: Duration = Duration::from_secs(15);
+
+/// Runs a closure, returning its result along with how long it took
+pub fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// The durations of repeated runs of a solution
+#[derive(Debug, Clone)]
+pub struct BenchResult {
+    /// Sorted from fastest to slowest
+    durations: Vec<Duration>,
+}
+
+impl BenchResult {
+    pub fn runs(&self) -> usize {
+        self.durations.len()
+    }
+
+    pub fn min(&self) -> Duration {
+        self.durations[0]
+    }
+
+    pub fn median(&self) -> Duration {
+        self.durations[self.durations.len() / 2]
+    }
+
+    pub fn max(&self) -> Duration {
+        self.durations[self.durations.len() - 1]
+    }
+
+    /// Whether a typical run takes longer than the `TIME_BUDGET`
+    pub fn exceeds_budget(&self) -> bool {
+        self.median() > TIME_BUDGET
+    }
+}
+
+/// Runs a solution `runs` times (at least once) against the same input
+pub fn bench(solution: &Solution, input: &str, runs: usize) -> Result<BenchResult> {
+    repeat(runs, || solution.solve(input))
+}
+
+/// Timings of an Intcode program run with and without the decode cache
+#[derive(Debug, Clone)]
+pub struct DecodeCacheBench {
+    /// Decoding every instruction each time it runs
+    pub uncached: BenchResult,
+    pub cached: BenchResult,
+}
+
+impl DecodeCacheBench {
+    /// How many times faster the median run is with the decode cache
+    pub fn speedup(&self) -> f64 {
+        self.uncached.median().as_secs_f64() / self.cached.median().as_secs_f64()
+    }
+
+    /// A table of the timings, followed by the speedup
+    pub fn report(&self) -> String {
+        let mut report = String::new();
+        for (name, result) in &[
+            ("decode every time", &self.uncached),
+            ("decode cache", &self.cached),
+        ] {
+            report.push_str(&format!(
+                "{:<20} min {:>12.3?}  median {:>12.3?}  max {:>12.3?}\n",
+                name,
+                result.min(),
+                result.median(),
+                result.max()
+            ));
+        }
+        report + &format!("\ndecode cache speedup: {:.2}x\n", self.speedup())
+    }
+}
+
+/// Runs an Intcode program `runs` times with `input`, both with and without the decode cache
+pub fn decode_cache(
+    program: &IntcodeProgram,
+    input: &[i64],
+    runs: usize,
+) -> Result<DecodeCacheBench> {
+    let run = |decode_cache| {
+        repeat(runs, || {
+            let mut computer = IntcodeComputer::from(program);
+            computer.set_decode_cache(decode_cache);
+            Ok(computer.run_with_input(input.to_vec())?)
+        })
+    };
+
+    Ok(DecodeCacheBench {
+        uncached: run(false)?,
+        cached: run(true)?,
+    })
+}
+
+/// Runs a closure `runs` times (at least once), stopping at the first error
+pub fn repeat<T>(runs: usize, mut f: impl FnMut() -> Result<T>) -> Result<BenchResult> {
+    let mut durations = Vec::with_capacity(runs);
+
+    for _ in 0..runs.max(1) {
+        let (result, duration) = time(&mut f);
+        result?;
+        durations.push(duration);
+    }
+
+    durations.sort();
+
+    Ok(BenchResult { durations })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{input, registry};
+
+    #[test]
+    fn test_bench() {
+        let solution = registry::find("2019::day1::part2").unwrap();
+        let result = bench(solution, "1969\n100756", 5).unwrap();
+
+        assert_eq!(result.runs(), 5);
+        assert!(result.min() <= result.median());
+        assert!(result.median() <= result.max());
+        assert!(!result.exceeds_budget());
+
+        assert_eq!(bench(solution, "1969\n100756", 0).unwrap().runs(), 1);
+    }
+
+    /// Compares 2019 day 9 part 2 with and without the decode cache, using the cached puzzle input. Run it with
+    /// `cargo test --release bench_decode_cache -- --ignored --nocapture`, after `advent-of-code fetch 2019 9`.
+    #[test]
+    #[ignore]
+    fn bench_decode_cache() {
+        let path = input::cache_path(2019, 9);
+        let program: IntcodeProgram = input::read_file(&path).unwrap().parse().unwrap();
+
+        print!("{}", decode_cache(&program, &[2], 10).unwrap().report());
+    }
+}
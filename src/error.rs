@@ -0,0 +1,137 @@
+use crate::year_2019::IntcodeError;
+use std::error;
+use std::fmt;
+use std::io;
+use std::result;
+
+/// A `Result` alias where the error defaults to this crate's `Error`
+pub type Result<T, E = Error> = result::Result<T, E>;
+
+/// Errors which can occur while solving a puzzle
+#[derive(Debug)]
+pub enum Error {
+    /// The puzzle input did not lead to an answer
+    NoSolution(String),
+
+    /// The puzzle input is malformed, at the given 1-based line and column
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    /// An I/O operation failed
+    Io { context: String, source: io::Error },
+
+    /// Downloading a puzzle input failed
+    Fetch(String),
+
+    /// A file of known-correct answers is malformed
+    Answers(String),
+
+    /// An Intcode program failed while running
+    Intcode(IntcodeError),
+
+    /// A solution selector (like `2019::day1..=day5`) could not be understood
+    Selector(String),
+
+    /// A saved Intcode snapshot is malformed
+    Snapshot(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSolution(reason) => write!(f, "no solution found: {}", reason),
+            Self::Parse {
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "invalid input at line {}, column {}: {}",
+                line, column, message
+            ),
+            Self::Io { context, source } => write!(f, "{}: {}", context, source),
+            Self::Fetch(reason) => write!(f, "failed to fetch puzzle input: {}", reason),
+            Self::Answers(reason) => write!(f, "invalid answers file: {}", reason),
+            Self::Intcode(error) => write!(f, "Intcode program failed: {}", error),
+            Self::Selector(reason) => write!(f, "invalid solution selector: {}", reason),
+            Self::Snapshot(reason) => write!(f, "invalid Intcode snapshot: {}", reason),
+        }
+    }
+}
+
+impl Error {
+    /// A parse error located at `fragment`, which should be a slice of `input`.
+    ///
+    /// If `fragment` isn't part of `input`, the error is located at the start of `input`.
+    pub fn parse(input: &str, fragment: &str, message: impl Into<String>) -> Self {
+        let offset = (fragment.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|offset| *offset <= input.len())
+            .unwrap_or(0);
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+
+        Self::Parse {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    /// Moves a parse error from a single line of input onto line `line` of the whole input
+    pub fn on_line(self, line: usize) -> Self {
+        match self {
+            Self::Parse {
+                line: offset,
+                column,
+                message,
+            } => Self::Parse {
+                line: line + offset - 1,
+                column,
+                message,
+            },
+            other => other,
+        }
+    }
+}
+
+impl From<IntcodeError> for Error {
+    fn from(error: IntcodeError) -> Self {
+        Self::Intcode(error)
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Intcode(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_location() {
+        let input = "1,2\n3,x,5\n";
+
+        match Error::parse(input, &input[6..7], "not a number") {
+            Error::Parse { line, column, .. } => assert_eq!((line, column), (2, 3)),
+            other => panic!("unexpected error {:?}", other),
+        }
+
+        let line = "#1 @ 1,x: 3x4";
+        let error = Error::parse(line, &line[7..8], "not a number").on_line(5);
+        assert_eq!(
+            error.to_string(),
+            "invalid input at line 5, column 8: not a number"
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use crate::{input, Error, Result};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+/// The session token used to download puzzle inputs
+pub const SESSION_ENV: &str = "AOC_SESSION";
+
+/// Overrides the server puzzle inputs are downloaded from
+pub const BASE_URL_ENV: &str = "AOC_BASE_URL";
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Identifies this tool to the Advent of Code servers, as requested by its maintainer
+pub const USER_AGENT: &str = concat!(
+    "advent-of-code/",
+    env!("CARGO_PKG_VERSION"),
+    " (github.com/alecdwm/advent-of-code by alec@owls.io)"
+);
+
+/// The minimum time between two downloads, across invocations
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Records when the last download happened, so that separate invocations share the rate limit
+const LAST_FETCH_FILENAME: &str = ".last-fetch";
+
+/// Downloads puzzle inputs into the input cache
+#[derive(Debug)]
+pub struct Fetcher {
+    base_url: String,
+    session: String,
+    inputs_dir: PathBuf,
+    min_interval: Duration,
+}
+
+impl Fetcher {
+    pub fn new(base_url: &str, session: &str, inputs_dir: &Path) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            inputs_dir: inputs_dir.to_path_buf(),
+            min_interval: DEFAULT_MIN_INTERVAL,
+        }
+    }
+
+    /// Configures a fetcher from the environment.
+    ///
+    /// The session token is read from `AOC_SESSION`, or if that's unset or empty, from the `advent-of-code/session` file
+    /// in the user's config directory. The base url is read from `AOC_BASE_URL`, and the inputs directory from
+    /// `AOC_INPUTS_DIR`.
+    pub fn from_env() -> Result<Self> {
+        let session = match env::var(SESSION_ENV) {
+            Ok(session) if !session.trim().is_empty() => session,
+            _ => read_session_file()?,
+        };
+        let base_url = env::var(BASE_URL_ENV).unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+
+        Ok(Self::new(&base_url, session.trim(), &input::inputs_dir()))
+    }
+
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    /// Downloads the input for a puzzle into the input cache, unless it is already cached.
+    ///
+    /// Returns the path of the cached input.
+    pub fn fetch(&self, year: u16, day: u8) -> Result<PathBuf> {
+        let path = input::cache_path_in(&self.inputs_dir, year, day);
+        if path.is_file() {
+            return Ok(path);
+        }
+
+        let input = self.download(year, day)?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|source| Error::Io {
+                context: format!("creating input cache directory {}", parent.display()),
+                source,
+            })?;
+        }
+        fs::write(&path, input).map_err(|source| Error::Io {
+            context: format!("writing puzzle input to {}", path.display()),
+            source,
+        })?;
+
+        Ok(path)
+    }
+
+    fn download(&self, year: u16, day: u8) -> Result<String> {
+        self.wait_for_rate_limit();
+
+        let url = format!("{}/{}/day/{}/input", self.base_url, year, day);
+        let response = ureq::AgentBuilder::new()
+            .user_agent(USER_AGENT)
+            .timeout(Duration::from_secs(30))
+            .build()
+            .get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call();
+
+        self.record_fetch()?;
+
+        match response {
+            Ok(response) => response.into_string().map_err(|source| Error::Io {
+                context: format!("reading response from {}", url),
+                source,
+            }),
+            Err(ureq::Error::Status(status, response)) => Err(Error::Fetch(format!(
+                "{} responded with {} {}",
+                url,
+                status,
+                response.status_text()
+            ))),
+            Err(error) => Err(Error::Fetch(format!("requesting {}: {}", url, error))),
+        }
+    }
+
+    fn wait_for_rate_limit(&self) {
+        let last_fetch = fs::metadata(self.inputs_dir.join(LAST_FETCH_FILENAME))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok());
+
+        if let Some(elapsed) = last_fetch {
+            if elapsed < self.min_interval {
+                thread::sleep(self.min_interval - elapsed);
+            }
+        }
+    }
+
+    fn record_fetch(&self) -> Result<()> {
+        let path = self.inputs_dir.join(LAST_FETCH_FILENAME);
+
+        fs::create_dir_all(&self.inputs_dir)
+            .and_then(|_| fs::write(&path, ""))
+            .map_err(|source| Error::Io {
+                context: format!("writing {}", path.display()),
+                source,
+            })
+    }
+}
+
+/// The location of the session token file, e.g. `~/.config/advent-of-code/session`
+pub fn session_file_path() -> Option<PathBuf> {
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config_dir.join("advent-of-code").join("session"))
+}
+
+fn read_session_file() -> Result<String> {
+    let missing_session = || {
+        Error::Fetch(format!(
+            "no session token found, set {} or write it to {}",
+            SESSION_ENV,
+            session_file_path()
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| "~/.config/advent-of-code/session".into())
+        ))
+    };
+
+    let path = session_file_path().ok_or_else(missing_session)?;
+    match fs::read_to_string(path) {
+        Ok(session) if !session.trim().is_empty() => Ok(session),
+        _ => Err(missing_session()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc::{self, Receiver};
+
+    /// Serves every request with the given status and body, sending back the request line and headers of each request
+    fn spawn_server(status: &'static str, body: &'static str) -> (String, Receiver<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (requests, received) = mpsc::channel();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let request: Vec<String> = BufReader::new(&stream)
+                    .lines()
+                    .map(|line| line.unwrap())
+                    .take_while(|line| !line.is_empty())
+                    .collect();
+
+                if requests.send(request).is_err() {
+                    return;
+                }
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        (base_url, received)
+    }
+
+    /// Checks that a request was for the 2019 day 9 input, with the session cookie and user agent set
+    fn assert_request(request: &[String]) {
+        let headers: Vec<String> = request.iter().map(|line| line.to_lowercase()).collect();
+
+        assert_eq!(request[0], "GET /2019/day/9/input HTTP/1.1");
+        assert!(headers.contains(&"cookie: session=secret".to_string()));
+        assert!(headers.contains(&format!("user-agent: {}", USER_AGENT).to_lowercase()));
+    }
+
+    fn temp_inputs_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("advent-of-code-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_fetch_caches_input() {
+        let (base_url, requests) = spawn_server("200 OK", "109,1,99\n");
+        let inputs_dir = temp_inputs_dir("fetch-caches-input");
+        let fetcher = Fetcher::new(&base_url, "secret", &inputs_dir)
+            .with_min_interval(Duration::from_secs(0));
+
+        let path = fetcher.fetch(2019, 9).unwrap();
+        assert_eq!(path, inputs_dir.join("2019").join("day9.txt"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "109,1,99\n");
+
+        fetcher.fetch(2019, 9).unwrap();
+        let requests: Vec<_> = requests.try_iter().collect();
+        assert_eq!(requests.len(), 1);
+        assert_request(&requests[0]);
+
+        fs::remove_dir_all(&inputs_dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_does_not_cache_errors() {
+        let (base_url, requests) = spawn_server("400 Bad Request", "Puzzle inputs differ by user.");
+        let inputs_dir = temp_inputs_dir("fetch-does-not-cache-errors");
+        let fetcher = Fetcher::new(&base_url, "secret", &inputs_dir)
+            .with_min_interval(Duration::from_secs(0));
+
+        assert!(matches!(fetcher.fetch(2019, 9), Err(Error::Fetch(_))));
+        assert_request(&requests.recv().unwrap());
+        assert!(!input::cache_path_in(&inputs_dir, 2019, 9).exists());
+
+        let _ = fs::remove_dir_all(&inputs_dir);
+    }
+}
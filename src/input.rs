@@ -0,0 +1,72 @@
+use crate::{Error, Result};
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal, Read};
+use std::path::{Path, PathBuf};
+
+/// Overrides the directory puzzle inputs are cached in (defaults to `inputs`)
+pub const INPUTS_DIR_ENV: &str = "AOC_INPUTS_DIR";
+
+/// The directory puzzle inputs are cached in
+pub fn inputs_dir() -> PathBuf {
+    env::var_os(INPUTS_DIR_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("inputs"))
+}
+
+/// The conventional location of a cached puzzle input, e.g. `inputs/2019/day9.txt`
+pub fn cache_path(year: u16, day: u8) -> PathBuf {
+    cache_path_in(&inputs_dir(), year, day)
+}
+
+/// The location of a cached puzzle input within a specific inputs directory
+pub fn cache_path_in(inputs_dir: &Path, year: u16, day: u8) -> PathBuf {
+    inputs_dir
+        .join(year.to_string())
+        .join(format!("day{}.txt", day))
+}
+
+/// Reads the input for a puzzle.
+///
+/// The input is read from `file` if given, otherwise from the input cache if the puzzle has a cached input,
+/// otherwise from stdin until EOF.
+pub fn read_input(year: u16, day: u8, file: Option<&Path>) -> Result<String> {
+    if let Some(file) = file {
+        return read_file(file);
+    }
+
+    let cached = cache_path(year, day);
+    if cached.is_file() {
+        return read_file(&cached);
+    }
+
+    read_stdin()
+}
+
+/// Reads a puzzle input from a file
+pub fn read_file(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|source| Error::Io {
+        context: format!("reading puzzle input from {}", path.display()),
+        source,
+    })
+}
+
+/// Reads a puzzle input from stdin until EOF, prompting for it when stdin is a terminal
+pub fn read_stdin() -> Result<String> {
+    let stdin = io::stdin();
+
+    if stdin.is_terminal() {
+        eprintln!("Enter puzzle input followed by EOF (Ctrl-D):");
+    }
+
+    let mut input = String::new();
+    stdin
+        .lock()
+        .read_to_string(&mut input)
+        .map_err(|source| Error::Io {
+            context: "reading puzzle input from stdin".into(),
+            source,
+        })?;
+
+    Ok(input)
+}
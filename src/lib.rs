@@ -2,8 +2,30 @@
 //!
 //! You don't need a computer science background to participate - just a little programming knowledge and some problem solving skills will get you pretty far. Nor do you need a fancy computer; every problem has a solution that completes in at most 15 seconds on ten-year-old hardware.
 
-/// Common code used between the various puzzles
-mod common;
+/// Reading puzzle input from files, the input cache or stdin
+pub mod input;
+
+/// Downloading puzzle input into the input cache
+pub mod fetch;
+
+/// Checking solutions against known-correct answers
+pub mod answers;
+
+/// Timing and benchmarking solutions
+pub mod bench;
+
+/// Running a selection of solutions and reporting their results
+pub mod runner;
+
+mod error;
+pub use error::{Error, Result};
+
+mod solution;
+pub use solution::{solve_with, Answer, Solved, Solver};
+
+/// Every puzzle solution, discovered at build time
+pub mod registry;
+pub use registry::Solution;
 
 /// "We've detected some temporal anomalies," one of Santa's Elves at the Temporal Anomaly Research and Detection Instrument Station tells you. She sounded pretty worried when she called you down here. "At 500-year intervals into the past, someone has been changing Santa's history!"
 ///
@@ -27,7 +49,32 @@ pub mod year_2018 {
 /// Collect stars by solving puzzles. Two puzzles will be made available on each day in the Advent calendar; the second puzzle is unlocked when you complete the first. Each puzzle grants one star. Good luck!
 pub mod year_2019 {
     pub mod intcode_computer;
-    pub use intcode_computer::{IntcodeComputer, IntcodeProgram};
+    pub use intcode_computer::{IntcodeComputer, IntcodeEvent, IntcodeProgram};
+
+    pub mod intcode_ascii;
+    pub use intcode_ascii::{AsciiEvent, IntcodeAscii};
+    pub mod intcode_assembler;
+    pub use intcode_assembler::assemble;
+    pub mod intcode_bigint;
+    pub use intcode_bigint::{BigIntcodeComputer, BigIntcodeEvent};
+    pub mod intcode_debugger;
+    pub use intcode_debugger::IntcodeDebugger;
+    pub mod intcode_disassembler;
+    pub use intcode_disassembler::{disassemble, DisassembledLine};
+    pub mod intcode_error;
+    pub use intcode_error::{IntcodeError, IntcodeErrorKind};
+
+    pub mod intcode_io;
+    pub use intcode_io::{InputIter, IntcodeInput, IntcodeOutput};
+    pub mod intcode_network;
+    pub use intcode_network::{IntcodeNetwork, NetworkEvent, Packet};
+    pub mod intcode_pipeline;
+    pub use intcode_pipeline::IntcodePipeline;
+    pub mod intcode_profiler;
+    pub use intcode_profiler::IntcodeProfile;
+    pub mod intcode_snapshot;
+    pub use intcode_snapshot::IntcodeSnapshot;
+    pub mod intcode_tracer;
 
     pub mod day1;
     pub mod day2;
@@ -1,75 +1,526 @@
-use std::collections::BTreeMap;
+use advent_of_code::answers::{AnswerStore, Verification};
+use advent_of_code::bench::{self, TIME_BUDGET};
+use advent_of_code::fetch::{self, Fetcher};
+use advent_of_code::year_2019::{
+    intcode_assembler, intcode_disassembler, BigIntcodeComputer, IntcodeAscii, IntcodeComputer,
+    IntcodeDebugger, IntcodeErrorKind, IntcodeProgram,
+};
+use advent_of_code::{input, registry, runner, Solution};
+use num_bigint::BigInt;
 use std::env;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How results of running solutions are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    /// One `{year, day, part, answer, duration_ms, parse_ms, solve_ms, error}` object per line
+    Json,
+}
 
 fn main() {
-    let mut puzzle_solutions: BTreeMap<&str, fn()> = BTreeMap::new();
-
-    puzzle_solutions.insert("2018::day1::part1", advent_of_code::year_2018::day1::part1);
-    puzzle_solutions.insert("2018::day1::part2", advent_of_code::year_2018::day1::part2);
-    puzzle_solutions.insert("2018::day2::part1", advent_of_code::year_2018::day2::part1);
-    puzzle_solutions.insert("2018::day2::part2", advent_of_code::year_2018::day2::part2);
-    puzzle_solutions.insert("2018::day3::part1", advent_of_code::year_2018::day3::part1);
-    puzzle_solutions.insert("2018::day3::part2", advent_of_code::year_2018::day3::part2);
-    puzzle_solutions.insert("2018::day4::part1", advent_of_code::year_2018::day4::part1);
-    puzzle_solutions.insert("2018::day4::part2", advent_of_code::year_2018::day4::part2);
-    puzzle_solutions.insert("2018::day5::part1", advent_of_code::year_2018::day5::part1);
-    puzzle_solutions.insert("2018::day5::part2", advent_of_code::year_2018::day5::part2);
-    puzzle_solutions.insert("2018::day6::part1", advent_of_code::year_2018::day6::part1);
-    puzzle_solutions.insert("2018::day6::part2", advent_of_code::year_2018::day6::part2);
-    puzzle_solutions.insert("2018::day7::part1", advent_of_code::year_2018::day7::part1);
-    puzzle_solutions.insert("2018::day7::part2", advent_of_code::year_2018::day7::part2);
-
-    puzzle_solutions.insert("2019::day1::part1", advent_of_code::year_2019::day1::part1);
-    puzzle_solutions.insert("2019::day1::part2", advent_of_code::year_2019::day1::part2);
-    puzzle_solutions.insert("2019::day2::part1", advent_of_code::year_2019::day2::part1);
-    puzzle_solutions.insert("2019::day2::part2", advent_of_code::year_2019::day2::part2);
-    puzzle_solutions.insert("2019::day3::part1", advent_of_code::year_2019::day3::part1);
-    puzzle_solutions.insert("2019::day3::part2", advent_of_code::year_2019::day3::part2);
-    puzzle_solutions.insert("2019::day4::part1", advent_of_code::year_2019::day4::part1);
-    puzzle_solutions.insert("2019::day4::part2", advent_of_code::year_2019::day4::part2);
-    puzzle_solutions.insert("2019::day5::part1", advent_of_code::year_2019::day5::part1);
-    puzzle_solutions.insert("2019::day5::part2", advent_of_code::year_2019::day5::part2);
-    puzzle_solutions.insert("2019::day6::part1", advent_of_code::year_2019::day6::part1);
-    puzzle_solutions.insert("2019::day6::part2", advent_of_code::year_2019::day6::part2);
-    puzzle_solutions.insert("2019::day7::part1", advent_of_code::year_2019::day7::part1);
-    puzzle_solutions.insert("2019::day7::part2", advent_of_code::year_2019::day7::part2);
-    puzzle_solutions.insert("2019::day8::part1", advent_of_code::year_2019::day8::part1);
-    puzzle_solutions.insert("2019::day8::part2", advent_of_code::year_2019::day8::part2);
-    puzzle_solutions.insert("2019::day9::part1", advent_of_code::year_2019::day9::part1);
-    puzzle_solutions.insert("2019::day9::part2", advent_of_code::year_2019::day9::part2);
-    puzzle_solutions.insert(
-        "2019::day10::part1",
-        advent_of_code::year_2019::day10::part1,
-    );
+    let mut input_file = None;
+    let mut format = Format::Text;
+    let mut record = false;
+    let mut time = false;
+    let mut bigint = false;
+    let mut runs = 10;
+    let mut commands = Vec::new();
 
-    let command = match env::args().nth(1) {
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => match args.next() {
+                Some(file) => input_file = Some(PathBuf::from(file)),
+                None => usage(),
+            },
+            "--format" => match args.next().as_deref() {
+                Some("text") => format = Format::Text,
+                Some("json") => format = Format::Json,
+                _ => usage(),
+            },
+            "--record" => record = true,
+            "--time" => time = true,
+            "--bigint" => bigint = true,
+            "--runs" => match args.next().and_then(|runs| runs.parse().ok()) {
+                Some(number) => runs = number,
+                None => usage(),
+            },
+            _ => commands.push(arg),
+        }
+    }
+
+    let command = match commands.first() {
         Some(command) => command,
+        None => usage(),
+    };
+
+    match command.as_str() {
+        "list" => {
+            for solution in registry::solutions() {
+                println!("{:<20} {}", solution.key(), solution.title);
+            }
+            std::process::exit(1);
+        }
+
+        "fetch" => {
+            let (year, day) = match (
+                commands.get(1).and_then(|year| year.parse().ok()),
+                commands.get(2).and_then(|day| day.parse().ok()),
+            ) {
+                (Some(year), Some(day)) => (year, day),
+                _ => usage(),
+            };
+
+            match Fetcher::from_env().and_then(|fetcher| fetcher.fetch(year, day)) {
+                Ok(path) => println!("{}", path.display()),
+                Err(error) => {
+                    eprintln!("Fetching input for {} day {} failed: {}", year, day, error);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        "verify" => verify(record),
+
+        "run" if commands.len() > 1 => run(&commands[1..], input_file.as_deref(), format),
+        "run" => usage(),
+
+        "bench" => match commands.get(1).map(String::as_str) {
+            Some("all") => {
+                for solution in registry::solutions() {
+                    let cached = input::cache_path(solution.year, solution.day);
+                    if cached.is_file() {
+                        bench_solution(solution, Some(&cached), runs);
+                    } else {
+                        println!("{:<20} missing input", solution.key());
+                    }
+                }
+            }
+            Some(puzzle_solution) => {
+                bench_solution(find_solution(puzzle_solution), input_file.as_deref(), runs)
+            }
+            None => usage(),
+        },
+
+        "intcode" => intcode(&commands[1..], runs, bigint),
+
+        puzzle_solution if format == Format::Json => {
+            let result = runner::run(&[find_solution(puzzle_solution)], input_file.as_deref());
+            println!("{}", result[0].to_json());
+            if result[0].error.is_some() {
+                std::process::exit(1);
+            }
+        }
+
+        puzzle_solution => {
+            let solution = find_solution(puzzle_solution);
+
+            let (input, read_duration) = bench::time(|| {
+                input::read_input(solution.year, solution.day, input_file.as_deref())
+            });
+            let (answer, parse_duration, solve_duration) = match input {
+                Ok(input) => {
+                    let solved = solution.solve_timed(&input);
+                    (solved.answer, solved.parse_duration, solved.solve_duration)
+                }
+                Err(error) => (Err(error), Duration::default(), Duration::default()),
+            };
+
+            match answer {
+                Ok(answer) => println!("{}", answer),
+                Err(error) => {
+                    eprintln!("Puzzle solution '{}' failed: {}", puzzle_solution, error);
+                    std::process::exit(1);
+                }
+            }
+
+            if time {
+                eprintln!(
+                    "read input: {:.3?}, parse: {:.3?}, solve: {:.3?}{}",
+                    read_duration,
+                    parse_duration,
+                    solve_duration,
+                    if parse_duration + solve_duration > TIME_BUDGET {
+                        " (over budget)"
+                    } else {
+                        ""
+                    }
+                );
+            }
+        }
+    }
+}
+
+fn find_solution(puzzle_solution: &str) -> &'static Solution {
+    match registry::find(puzzle_solution) {
+        Some(solution) => solution,
         None => {
-            eprintln!(
-                "Usage: advent-of-code <command>\n\nCommands:\n\tlist{}",
-                puzzle_solutions
-                    .keys()
-                    .map(|key| format!("\n\t{}", key))
-                    .collect::<String>()
+            eprintln!("Puzzle solution '{}' not found", puzzle_solution);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Runs a solution repeatedly and reports the fastest, median and slowest run
+fn bench_solution(solution: &Solution, input_file: Option<&Path>, runs: usize) {
+    let result = input::read_input(solution.year, solution.day, input_file)
+        .and_then(|input| bench::bench(solution, &input, runs));
+
+    match result {
+        Ok(result) => println!(
+            "{:<20} min {:>12.3?}  median {:>12.3?}  max {:>12.3?}{}",
+            solution.key(),
+            result.min(),
+            result.median(),
+            result.max(),
+            if result.exceeds_budget() {
+                "  SLOW"
+            } else {
+                ""
+            }
+        ),
+        Err(error) => println!("{:<20} ERROR ({})", solution.key(), error),
+    }
+}
+
+/// Runs every solution matched by the selectors in order, then prints a table of answers and timings
+fn run(selectors: &[String], input_file: Option<&Path>, format: Format) {
+    let mut selected: Vec<&Solution> = Vec::new();
+    for selector in selectors {
+        match registry::select(selector) {
+            Ok(solutions) => selected.extend(solutions),
+            Err(error) => {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let single_day = selected
+        .windows(2)
+        .all(|pair| (pair[0].year, pair[0].day) == (pair[1].year, pair[1].day));
+    if input_file.is_some() && !single_day {
+        eprintln!("--input can only be used when running the parts of a single day");
+        std::process::exit(1);
+    }
+
+    let results = runner::run(&selected, input_file);
+    let failed = results
+        .iter()
+        .filter(|result| result.error.is_some())
+        .count();
+
+    match format {
+        Format::Json => {
+            for result in &results {
+                println!("{}", result.to_json());
+            }
+        }
+        Format::Text => {
+            println!("{:<20} {:>12}  Answer", "Solution", "Time");
+            for result in &results {
+                let answer = match (&result.answer, &result.error) {
+                    (Some(answer), _) => answer.to_string(),
+                    (None, Some(error)) => format!("ERROR ({})", error),
+                    (None, None) => String::new(),
+                };
+
+                let mut lines = answer.lines();
+                println!(
+                    "{:<20} {:>12.3?}  {}",
+                    result.key(),
+                    result.duration,
+                    lines.next().unwrap_or_default()
+                );
+                for line in lines {
+                    println!("{:<20} {:>12}  {}", "", "", line);
+                }
+            }
+
+            println!(
+                "\n{} solved, {} failed in {:.3?}",
+                results.len() - failed,
+                failed,
+                results
+                    .iter()
+                    .map(|result| result.duration)
+                    .sum::<Duration>()
             );
+        }
+    }
+
+    if failed > 0 {
+        std::process::exit(1);
+    }
+}
+
+/// Runs one of the Intcode tools on a program file
+fn intcode(commands: &[String], runs: usize, bigint: bool) {
+    let (tool, file, arguments) = match commands {
+        [tool, file] => (tool.as_str(), file, &[][..]),
+        [tool, file, arguments @ ..]
+            if ["run", "trace", "bench", "profile"].contains(&tool.as_str()) =>
+        {
+            (tool.as_str(), file, arguments)
+        }
+        _ => usage(),
+    };
+    if bigint {
+        match tool {
+            "run" => intcode_bigint(file, arguments),
+            _ => usage(),
+        }
+        return;
+    }
+
+    let input = match arguments
+        .iter()
+        .map(|value| value.parse())
+        .collect::<Result<Vec<i64>, _>>()
+    {
+        Ok(input) => input,
+        Err(_) => usage(),
+    };
+
+    let source = read_intcode_source(file);
+    let program = match tool {
+        "asm" => intcode_assembler::assemble(&source),
+        _ => source.parse::<IntcodeProgram>(),
+    };
+    let program = match program {
+        Ok(program) => program,
+        Err(error) => {
+            eprintln!("Reading Intcode program '{}' failed: {}", file, error);
             std::process::exit(1);
         }
     };
 
-    match command.as_str() {
-        "list" => {
-            for puzzle_solution in puzzle_solutions.keys() {
-                println!("{}", puzzle_solution);
+    match tool {
+        "asm" => println!("{}", program.data_serialized()),
+        "run" => match IntcodeComputer::from(&program).run_with_input(input) {
+            Ok(output) => {
+                for value in output {
+                    println!("{}", value);
+                }
+            }
+            Err(error) => {
+                eprintln!("Intcode program failed: {}", error);
+                if error.kind == IntcodeErrorKind::Overflow {
+                    eprintln!("Run it with --bigint if it needs values larger than 64 bits");
+                }
+                std::process::exit(1);
+            }
+        },
+        "disasm" => print!("{}", intcode_disassembler::disassemble(&program)),
+        "debug" => {
+            let stdin = std::io::stdin();
+            let mut debugger = IntcodeDebugger::new(IntcodeComputer::from(&program));
+            if let Err(error) = debugger.run(stdin.lock(), std::io::stdout()) {
+                eprintln!("Debugger failed: {}", error);
+                std::process::exit(1);
+            }
+        }
+        "ascii" => {
+            let stdin = std::io::stdin();
+            let mut ascii = IntcodeAscii::new(IntcodeComputer::from(&program));
+            match ascii.run_interactive(stdin.lock(), std::io::stdout()) {
+                Ok(Some(answer)) => println!("answer: {}", answer),
+                Ok(None) => {}
+                Err(error) => {
+                    eprintln!("{}", error);
+                    std::process::exit(1);
+                }
+            }
+        }
+        "trace" => {
+            let mut computer = IntcodeComputer::from(&program);
+            computer.set_tracer(std::io::stdout());
+            if let Err(error) = computer.run_with_input(input) {
+                eprintln!("Intcode program failed: {}", error);
+                std::process::exit(1);
             }
-            std::process::exit(1);
         }
+        "profile" => {
+            let mut computer = IntcodeComputer::from(&program);
+            computer.set_profiling(true);
+            let result = computer.run_with_input(input);
 
-        puzzle_solution => match puzzle_solutions.get(puzzle_solution) {
-            Some(puzzle_solution_fn) => puzzle_solution_fn(),
-            None => {
-                eprintln!("Puzzle solution '{}' not found", puzzle_solution);
+            print!("{}", computer.profile().unwrap().report(&computer.memory));
+            if let Err(error) = result {
+                eprintln!("Intcode program failed: {}", error);
+                std::process::exit(1);
+            }
+        }
+        "bench" => match bench::decode_cache(&program, &input, runs) {
+            Ok(result) => print!("{}", result.report()),
+            Err(error) => {
+                eprintln!("Intcode program failed: {}", error);
                 std::process::exit(1);
             }
         },
+        _ => usage(),
+    }
+}
+
+/// Runs the Intcode program in `file` on a `BigIntcodeComputer`, printing each value it outputs
+fn intcode_bigint(file: &str, arguments: &[String]) {
+    let input = match arguments
+        .iter()
+        .map(|value| value.parse())
+        .collect::<Result<Vec<BigInt>, _>>()
+    {
+        Ok(input) => input,
+        Err(_) => usage(),
+    };
+
+    let source = read_intcode_source(file);
+    let mut computer: BigIntcodeComputer = match source.parse() {
+        Ok(computer) => computer,
+        Err(error) => {
+            eprintln!("Reading Intcode program '{}' failed: {}", file, error);
+            std::process::exit(1);
+        }
+    };
+
+    match computer.run_with_input(input) {
+        Ok(output) => {
+            for value in output {
+                println!("{}", value);
+            }
+        }
+        Err(error) => {
+            eprintln!("Intcode program failed: {}", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn read_intcode_source(file: &str) -> String {
+    match std::fs::read_to_string(file) {
+        Ok(source) => source,
+        Err(error) => {
+            eprintln!("Reading '{}' failed: {}", file, error);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Checks every solution with a cached input against its known-correct answer
+fn verify(record: bool) {
+    let store = AnswerStore::from_env();
+    let inputs_dir = input::inputs_dir();
+
+    let (mut passed, mut failed, mut missing, mut recorded) = (0, 0, 0, 0);
+
+    for solution in registry::solutions() {
+        let verification = store.verify(solution, &inputs_dir);
+
+        match &verification {
+            Verification::Pass => passed += 1,
+            Verification::Fail { .. } | Verification::Error(_) => failed += 1,
+            Verification::MissingInput => missing += 1,
+            Verification::MissingAnswer(answer) if record => {
+                match store.record(solution.year, solution.day, solution.part, answer) {
+                    Ok(()) => {
+                        recorded += 1;
+                        println!("{:<20} recorded {}", solution.key(), answer);
+                        continue;
+                    }
+                    Err(error) => {
+                        eprintln!("Recording answer for '{}' failed: {}", solution, error);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            Verification::MissingAnswer(_) => missing += 1,
+        }
+
+        println!("{:<20} {}", solution.key(), verification);
+    }
+
+    print!(
+        "\n{} passed, {} failed, {} missing",
+        passed, failed, missing
+    );
+    if record {
+        print!(", {} recorded", recorded);
+    }
+    println!();
+
+    if failed > 0 {
+        std::process::exit(1);
     }
 }
+
+/// The usage text, with `{solutions}`, `{inputs}`, `{session_env}`, `{session_file}`, `{answers}` and `{budget}` filled
+/// in by `usage`
+const USAGE: &str = "\
+Usage: advent-of-code <command> [--input <file>] [--format text|json] [--time]
+
+Commands:
+\tlist
+\trun <selector>...
+\tfetch <year> <day>
+\tverify [--record]
+\tbench <solution|all> [--runs <n>]
+\tintcode asm|disasm|debug|ascii <file>
+\tintcode run <file> [<input>...] [--bigint]
+\tintcode trace|profile|bench <file> [<input>...]{solutions}
+
+Puzzle input is read from --input if given, otherwise from {inputs}/<year>/day<N>.txt if it exists, otherwise from
+stdin.
+
+run accepts selectors like all, 2019, 2019::day7, 2019::day7::part2 or 2019::day1..=day5, and runs every matching
+solution in order. When more than one day is selected, input is only read from the input cache.
+
+--format json prints one {year, day, part, answer, duration_ms, parse_ms, solve_ms, error} object per line when running
+solutions. duration_ms is parse_ms and solve_ms together.
+
+--time reports how long reading the input took, including any wait on stdin, how long the day's input took to parse,
+and how long the part took to solve it. A part is over budget when parsing and solving take more than {budget} together.
+
+fetch downloads puzzle input into {inputs}/<year>/day<N>.txt, using the session token from ${session_env} or
+{session_file}.
+
+verify runs every solution against its cached input and compares the answer with {answers}/<year>/day<N>.toml.
+--record saves answers for parts which have none yet.
+
+bench runs a solution --runs times (default 10) and flags solutions whose median exceeds the {budget} budget.
+
+intcode asm assembles the Intcode assembly in <file> and prints the program.
+intcode disasm prints a listing of the Intcode program in <file>, in the same syntax.
+intcode debug runs the Intcode program in <file> in an interactive debugger; enter help for its commands.
+intcode ascii runs it as an ASCII program, sending each line read from stdin as character codes and printing its text
+output; any output value which isn't ASCII is printed as its answer.
+intcode run runs it with the given input values and prints each value it outputs. With --bigint, it runs with
+arbitrary-precision values instead of 64-bit ones, for programs whose values overflow.
+intcode trace runs it with the given input values and prints every instruction it executes.
+intcode profile runs it and prints how many times each instruction ran.
+intcode bench runs it --runs times with and without the decode cache and compares the timings.";
+
+fn usage() -> ! {
+    let solutions: String = registry::solutions()
+        .iter()
+        .map(|solution| format!("\n\t{}", solution))
+        .collect();
+    let session_file = fetch::session_file_path()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| "~/.config/advent-of-code/session".into());
+
+    eprintln!(
+        "{}",
+        USAGE
+            .replace("{solutions}", &solutions)
+            .replace("{inputs}", &input::inputs_dir().display().to_string())
+            .replace("{session_env}", fetch::SESSION_ENV)
+            .replace("{session_file}", &session_file)
+            .replace(
+                "{answers}",
+                &AnswerStore::from_env().dir().display().to_string()
+            )
+            .replace("{budget}", &format!("{:?}", TIME_BUDGET))
+    );
+    std::process::exit(1);
+}
@@ -0,0 +1,193 @@
+use crate::{Answer, Error, Result, Solved, Solver};
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// Every puzzle solution in this crate, ordered by year, day and part.
+///
+/// Generated by `build.rs`, which discovers each `pub fn part1` and `pub fn part2` in `src/year_*/day*.rs`, and pairs
+/// them with the `pub fn parse` of the same day. Each part is wrapped in a closure so that parsed input like a `Vec<T>`
+/// can be passed to a part taking `&[T]`, which clippy considers redundant for the parts that don't need it.
+#[allow(clippy::redundant_closure)]
+static SOLUTIONS: &[Solution] = include!(concat!(env!("OUT_DIR"), "/solutions.rs"));
+
+/// A solution to one part of a puzzle, along with the metadata identifying it
+#[derive(Debug, Clone, Copy)]
+pub struct Solution {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    /// The puzzle title, taken from the `//! --- Day N: <title> ---` header of the day module
+    pub title: &'static str,
+    pub solver: Solver,
+}
+
+impl Solution {
+    /// The key used to select this solution from the command line, e.g. `2019::day9::part2`
+    pub fn key(&self) -> String {
+        self.to_string()
+    }
+
+    pub fn solve(&self, input: &str) -> Result<Answer> {
+        (self.solver)(input).answer
+    }
+
+    /// Solves the puzzle, also returning how long parsing and solving took
+    pub fn solve_timed(&self, input: &str) -> Solved {
+        (self.solver)(input)
+    }
+}
+
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::day{}::part{}", self.year, self.day, self.part)
+    }
+}
+
+/// All registered solutions, ordered by year, day and part
+pub fn solutions() -> &'static [Solution] {
+    SOLUTIONS
+}
+
+/// Finds a registered solution by its key, e.g. `2019::day9::part2`
+pub fn find(key: &str) -> Option<&'static Solution> {
+    SOLUTIONS.iter().find(|solution| solution.key() == key)
+}
+
+/// Selects registered solutions, in order, using a selector like:
+///
+/// - `all` for every solution
+/// - `2019` for every solution in a year
+/// - `2019::day7` for both parts of a day
+/// - `2019::day7::part2` for a single part
+/// - `2019::day1..=day5` or `2019::day1..day6` for a range of days
+pub fn select(selector: &str) -> Result<Vec<&'static Solution>> {
+    let invalid = |reason: &str| Error::Selector(format!("'{}' {}", selector, reason));
+
+    if selector == "all" {
+        return Ok(SOLUTIONS.iter().collect());
+    }
+
+    let mut segments = selector.split("::");
+    let year: u16 = segments
+        .next()
+        .and_then(|year| year.parse().ok())
+        .ok_or_else(|| invalid("does not start with a year"))?;
+    let days = match segments.next() {
+        None => 1..=u8::MAX,
+        Some(days) => parse_days(days).ok_or_else(|| invalid("has an invalid day or day range"))?,
+    };
+    let part: Option<u8> = match segments.next() {
+        None => None,
+        Some(part) => Some(
+            part.strip_prefix("part")
+                .and_then(|part| part.parse().ok())
+                .ok_or_else(|| invalid("has an invalid part"))?,
+        ),
+    };
+    if segments.next().is_some() {
+        return Err(invalid("has too many segments"));
+    }
+
+    let selected: Vec<_> = SOLUTIONS
+        .iter()
+        .filter(|solution| solution.year == year)
+        .filter(|solution| days.contains(&solution.day))
+        .filter(|solution| part.map_or(true, |part| solution.part == part))
+        .collect();
+
+    if selected.is_empty() {
+        return Err(invalid("does not match any solutions"));
+    }
+
+    Ok(selected)
+}
+
+/// Parses `day7`, `day1..=day5` or `day1..day6`
+fn parse_days(days: &str) -> Option<RangeInclusive<u8>> {
+    let parse_day = |day: &str| day.strip_prefix("day")?.parse::<u8>().ok();
+
+    if let Some((start, end)) = days.split_once("..=") {
+        Some(parse_day(start)?..=parse_day(end)?)
+    } else if let Some((start, end)) = days.split_once("..") {
+        Some(parse_day(start)?..=parse_day(end)?.checked_sub(1)?)
+    } else {
+        let day = parse_day(days)?;
+        Some(day..=day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solutions_are_discovered() {
+        let solution = find("2019::day9::part2").expect("Missing 2019::day9::part2");
+
+        assert_eq!(solution.year, 2019);
+        assert_eq!(solution.day, 9);
+        assert_eq!(solution.part, 2);
+        assert_eq!(solution.title, "Sensor Boost");
+
+        assert!(find("2018::day1::part1").is_some());
+        assert!(find("2019::day10::part2").is_none());
+    }
+
+    #[test]
+    fn test_solutions_are_ordered() {
+        let keys: Vec<_> = solutions()
+            .iter()
+            .map(|solution| (solution.year, solution.day, solution.part))
+            .collect();
+
+        let mut sorted_keys = keys.clone();
+        sorted_keys.sort();
+        sorted_keys.dedup();
+
+        assert_eq!(keys, sorted_keys);
+    }
+
+    #[test]
+    fn test_select() {
+        let keys = |selector| -> Vec<String> {
+            select(selector)
+                .unwrap()
+                .iter()
+                .map(|solution| solution.key())
+                .collect()
+        };
+
+        assert_eq!(keys("all").len(), solutions().len());
+        assert!(keys("2019").iter().all(|key| key.starts_with("2019::")));
+        assert_eq!(
+            keys("2019::day7"),
+            ["2019::day7::part1", "2019::day7::part2"]
+        );
+        assert_eq!(keys("2019::day7::part2"), ["2019::day7::part2"]);
+        assert_eq!(keys("2019::day2..=day3"), keys("2019::day2..day4"));
+        assert_eq!(
+            keys("2019::day2..=day3"),
+            [
+                "2019::day2::part1",
+                "2019::day2::part2",
+                "2019::day3::part1",
+                "2019::day3::part2"
+            ]
+        );
+
+        for invalid in &[
+            "",
+            "day7",
+            "2019::7",
+            "2019::day7::2",
+            "2019::day7::part1::x",
+            "2019::day30",
+        ] {
+            assert!(
+                matches!(select(invalid), Err(Error::Selector(_))),
+                "{}",
+                invalid
+            );
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{input, Answer, Solution, Solved};
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+
+/// The outcome of running one solution, as reported by `--format json`
+#[derive(Debug, Clone, Serialize)]
+pub struct RunResult {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<Answer>,
+    /// How long parsing and solving took together
+    #[serde(rename = "duration_ms", serialize_with = "serialize_millis")]
+    pub duration: Duration,
+    #[serde(rename = "parse_ms", serialize_with = "serialize_millis")]
+    pub parse_duration: Duration,
+    #[serde(rename = "solve_ms", serialize_with = "serialize_millis")]
+    pub solve_duration: Duration,
+    pub error: Option<String>,
+}
+
+impl RunResult {
+    pub fn key(&self) -> String {
+        format!("{}::day{}::part{}", self.year, self.day, self.part)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("RunResult always serializes")
+    }
+}
+
+fn serialize_millis<S: serde::Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64() * 1000.0)
+}
+
+/// Runs each solution in order, reading each day's input once and sharing it between its parts.
+///
+/// Input is read from `input_file` if given, otherwise from the input cache. If every solution belongs to the same
+/// day, input falls back to stdin when it isn't cached.
+pub fn run(solutions: &[&Solution], input_file: Option<&Path>) -> Vec<RunResult> {
+    let single_day = solutions
+        .windows(2)
+        .all(|pair| (pair[0].year, pair[0].day) == (pair[1].year, pair[1].day));
+
+    // Errors are kept as text, as reading a day's input can fail for both of its parts
+    let mut day_input: Option<((u16, u8), Result<String, String>)> = None;
+
+    solutions
+        .iter()
+        .map(|solution| {
+            let day = (solution.year, solution.day);
+            if day_input.as_ref().map(|(input_day, _)| *input_day) != Some(day) {
+                let cached = input::cache_path(solution.year, solution.day);
+                let file = match input_file {
+                    Some(file) => Some(file),
+                    None if single_day => None,
+                    None => Some(cached.as_path()),
+                };
+
+                let input = input::read_input(solution.year, solution.day, file);
+                day_input = Some((day, input.map_err(|error| error.to_string())));
+            }
+
+            let (solved, error) = match &day_input {
+                Some((_, Ok(input))) => {
+                    let solved = solution.solve_timed(input);
+                    let error = solved.answer.as_ref().err().map(|error| error.to_string());
+                    (Some(solved), error)
+                }
+                Some((_, Err(error))) => (None, Some(error.clone())),
+                None => unreachable!("the input for this day was read above"),
+            };
+
+            RunResult {
+                year: solution.year,
+                day: solution.day,
+                part: solution.part,
+                duration: solved.as_ref().map(Solved::duration).unwrap_or_default(),
+                parse_duration: solved
+                    .as_ref()
+                    .map(|solved| solved.parse_duration)
+                    .unwrap_or_default(),
+                solve_duration: solved
+                    .as_ref()
+                    .map(|solved| solved.solve_duration)
+                    .unwrap_or_default(),
+                answer: solved.and_then(|solved| solved.answer.ok()),
+                error,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+    use std::{env, fs};
+
+    #[test]
+    fn test_run() {
+        let path = env::temp_dir().join(format!("advent-of-code-run-{}.txt", std::process::id()));
+        fs::write(&path, "1969\n100756\n").unwrap();
+
+        let solutions = registry::select("2019::day1").unwrap();
+        let results = run(&solutions, Some(&path));
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].answer, Some(Answer::Integer(34237)));
+        assert_eq!(results[1].answer, Some(Answer::Integer(51312)));
+        assert_eq!(
+            results[1].duration,
+            results[1].parse_duration + results[1].solve_duration
+        );
+        assert!(results[1]
+            .to_json()
+            .starts_with(r#"{"year":2019,"day":1,"part":2,"answer":51312,"duration_ms":"#));
+        assert!(results[1].to_json().ends_with(r#","error":null}"#));
+
+        fs::remove_file(&path).unwrap();
+        let results = run(&solutions, Some(&path));
+        assert!(results.iter().all(|result| result.error.is_some()));
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{bench, Result};
+use serde::Serialize;
+use std::fmt;
+use std::time::Duration;
+
+/// Solves one part of a puzzle, given the puzzle input, timing how long parsing and solving took
+pub type Solver = fn(&str) -> Solved;
+
+/// The outcome of solving one part of a puzzle
+#[derive(Debug)]
+pub struct Solved {
+    pub answer: Result<Answer>,
+
+    /// How long the day's `parse` took
+    pub parse_duration: Duration,
+
+    /// How long the part took once the input was parsed, or zero if parsing failed
+    pub solve_duration: Duration,
+}
+
+impl Solved {
+    /// How long parsing and solving took together
+    pub fn duration(&self) -> Duration {
+        self.parse_duration + self.solve_duration
+    }
+}
+
+/// Parses `input` with a day's `parse` function, then solves the parsed input with one of its parts, timing each step.
+///
+/// The solution registry generated by `build.rs` calls this for every part.
+pub fn solve_with<'a, T>(
+    input: &'a str,
+    parse: impl FnOnce(&'a str) -> Result<T>,
+    part: impl FnOnce(&T) -> Result<Answer>,
+) -> Solved {
+    let (parsed, parse_duration) = bench::time(|| parse(input));
+    let (answer, solve_duration) = match parsed {
+        Ok(parsed) => bench::time(|| part(&parsed)),
+        Err(error) => (Err(error), Duration::default()),
+    };
+
+    Solved {
+        answer,
+        parse_duration,
+        solve_duration,
+    }
+}
+
+/// The answer to one part of a puzzle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(integer) => write!(f, "{}", integer),
+            Self::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+impl Serialize for Answer {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Integer(integer) => serializer.serialize_i64(*integer),
+            Self::Text(text) => serializer.serialize_str(text),
+        }
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(integer: i64) -> Self {
+        Self::Integer(integer)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(integer: usize) -> Self {
+        Self::Integer(integer as i64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
@@ -6,39 +6,42 @@
 //!
 //! "Wouldn't they have had enough fabric to fill several boxes in the warehouse? They'd be stored together, so the box IDs should be similar. Too bad it would take forever to search the warehouse for two similar box IDs..." They walk too far away to hear any more.
 
+use crate::{Answer, Error, Result};
 use std::collections::BTreeMap;
 
+/// Splits the puzzle input into box IDs
+pub fn parse(input: &str) -> Result<Vec<&str>> {
+    Ok(input.lines().collect())
+}
+
 /// Late at night, you sneak to the warehouse - who knows what kinds of paradoxes you could cause if you were discovered - and use your fancy wrist device to quickly scan every box and produce a list of the likely candidates (your puzzle input).
 ///
 /// To make sure you didn't miss any, you scan the likely candidate boxes again, counting the number that have an ID containing exactly two of any letter and then separately counting those with exactly three of any letter. You can multiply those two counts together to get a rudimentary checksum and compare it to what your device predicts.
 ///
 /// For example, if you see the following box IDs:
 ///
-///     abcdef contains no letters that appear exactly two or three times.
-///     bababc contains two a and three b, so it counts for both.
-///     abbcde contains two b, but no letter appears exactly three times.
-///     abcccd contains three c, but no letter appears exactly two times.
-///     aabcdd contains two a and two d, but it only counts once.
-///     abcdee contains two e.
-///     ababab contains three a and three b, but it only counts once.
+/// ```text
+/// abcdef contains no letters that appear exactly two or three times.
+/// bababc contains two a and three b, so it counts for both.
+/// abbcde contains two b, but no letter appears exactly three times.
+/// abcccd contains three c, but no letter appears exactly two times.
+/// aabcdd contains two a and two d, but it only counts once.
+/// abcdee contains two e.
+/// ababab contains three a and three b, but it only counts once.
+/// ```
 ///
 /// Of these box IDs, four of them contain a letter which appears exactly twice, and three of them contain a letter which appears exactly three times. Multiplying these together produces a checksum of 4 * 3 = 12.
 ///
 /// What is the checksum for your list of box IDs?
-pub fn part1() {
-    let input = crate::common::read_stdin_to_string();
-
+pub fn part1(box_ids: &[&str]) -> Result<Answer> {
     let mut two_letter_checksum_component: i64 = 0;
     let mut three_letter_checksum_component: i64 = 0;
 
     let mut seen_letter_counts: BTreeMap<char, i64> = BTreeMap::new();
 
-    for box_id in input.lines() {
+    for box_id in box_ids {
         for letter in box_id.chars() {
-            let count = (match seen_letter_counts.get(&letter) {
-                Some(count) => count,
-                None => &0,
-            }) + 1;
+            let count = seen_letter_counts.get(&letter).unwrap_or(&0) + 1;
             seen_letter_counts.insert(letter, count);
         }
 
@@ -63,7 +66,7 @@ pub fn part1() {
 
     let checksum = two_letter_checksum_component * three_letter_checksum_component;
 
-    println!("the checksum for your list of box IDs: {}", checksum);
+    Ok(checksum.into())
 }
 
 /// Confident that your list of box IDs is complete, you're ready to find the boxes full of prototype fabric.
@@ -81,10 +84,9 @@ pub fn part1() {
 /// The IDs abcde and axcye are close, but they differ by two characters (the second and fourth). However, the IDs fghij and fguij differ by exactly one character, the third (h and u). Those must be the correct boxes.
 ///
 /// What letters are common between the two correct box IDs? (In the example above, this is found by removing the differing character from either ID, producing fgij.)
-pub fn part2() {
-    let input = crate::common::read_stdin_to_string();
-
-    let matches = find_part2_matches(&input).expect("No matches found");
+pub fn part2(box_ids: &[&str]) -> Result<Answer> {
+    let matches = find_part2_matches(box_ids)
+        .ok_or_else(|| Error::NoSolution("no two box IDs differ by exactly one letter".into()))?;
 
     let common_letters: String = matches
         .0
@@ -94,15 +96,12 @@ pub fn part2() {
         .map(|letters| letters.0)
         .collect();
 
-    println!(
-        "the common letters between the two correct box IDs: {}",
-        common_letters
-    );
+    Ok(common_letters.into())
 }
 
-fn find_part2_matches(input: &str) -> Option<(String, String)> {
-    for box_id_1 in input.lines() {
-        'test_inner: for box_id_2 in input.lines() {
+fn find_part2_matches(box_ids: &[&str]) -> Option<(String, String)> {
+    for box_id_1 in box_ids {
+        'test_inner: for box_id_2 in box_ids {
             if box_id_1 == box_id_2 {
                 continue;
             }
@@ -4,16 +4,30 @@
 //!
 //! The whole piece of fabric they're working on is a very large square - at least 1000 inches on each side.
 
+use crate::{Answer, Error, Result};
 use std::collections::BTreeMap;
-use std::num::ParseIntError;
 use std::str::FromStr;
 
+/// Parses every claim in the puzzle input
+pub fn parse(input: &str) -> Result<Vec<FabricClaim>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(line_index, line)| {
+            line.parse()
+                .map_err(|error: Error| error.on_line(line_index + 1))
+        })
+        .collect()
+}
+
 /// Each Elf has made a claim about which area of fabric would be ideal for Santa's suit. All claims have an ID and consist of a single rectangle with edges parallel to the edges of the fabric. Each claim's rectangle is defined as follows:
 ///
-///     The number of inches between the left edge of the fabric and the left edge of the rectangle.
-///     The number of inches between the top edge of the fabric and the top edge of the rectangle.
-///     The width of the rectangle in inches.
-///     The height of the rectangle in inches.
+/// ```text
+/// The number of inches between the left edge of the fabric and the left edge of the rectangle.
+/// The number of inches between the top edge of the fabric and the top edge of the rectangle.
+/// The width of the rectangle in inches.
+/// The height of the rectangle in inches.
+/// ```
 ///
 /// A claim like #123 @ 3,2: 5x4 means that claim ID 123 specifies a rectangle 3 inches from the left edge, 2 inches from the top edge, 5 inches wide, and 4 inches tall. Visually, it claims the square inches of fabric represented by # (and ignores the square inches of fabric represented by .) in the diagram below:
 ///
@@ -47,13 +61,10 @@ use std::str::FromStr;
 /// The four square inches marked with X are claimed by both 1 and 2. (Claim 3, while adjacent to the others, does not overlap either of them.)
 ///
 /// If the Elves all proceed with their own plans, none of them will have enough fabric. How many square inches of fabric are within two or more claims?
-pub fn part1() {
-    let input = crate::common::read_stdin_to_string();
-
+pub fn part1(claims: &[FabricClaim]) -> Result<Answer> {
     let mut fabric: BTreeMap<(i64, i64), u8> = BTreeMap::new();
 
-    for line in input.lines() {
-        let claim: FabricClaim = line.parse().expect("Parsing fabric claim");
+    for claim in claims {
         for w in 0..claim.width {
             for h in 0..claim.height {
                 let index = (claim.pos_x + w, claim.pos_y + h);
@@ -64,10 +75,7 @@ pub fn part1() {
 
     let contested_square_inches = fabric.values().filter(|v| **v > 1).count();
 
-    println!(
-        "the number of square inches of fabric within two or more claims: {}",
-        contested_square_inches
-    );
+    Ok(contested_square_inches.into())
 }
 
 /// Amidst the chaos, you notice that exactly one claim doesn't overlap by even a single square inch of fabric with any other claim. If you can somehow draw attention to it, maybe the Elves will be able to make Santa's suit after all!
@@ -75,24 +83,19 @@ pub fn part1() {
 /// For example, in the claims above, only claim 3 is intact after all claims are made.
 ///
 /// What is the ID of the only claim that doesn't overlap?
-pub fn part2() {
-    let input = crate::common::read_stdin_to_string();
-
+pub fn part2(claims: &[FabricClaim]) -> Result<Answer> {
     let mut fabric: BTreeMap<(i64, i64), u8> = BTreeMap::new();
-    let mut claims: Vec<FabricClaim> = Vec::new();
 
-    for line in input.lines() {
-        let claim: FabricClaim = line.parse().expect("Parsing fabric claim");
+    for claim in claims {
         for w in 0..claim.width {
             for h in 0..claim.height {
                 let index = (claim.pos_x + w, claim.pos_y + h);
                 *fabric.entry(index).or_insert(0) += 1;
             }
         }
-        claims.push(line.parse().expect("Parsing fabric claim"));
     }
 
-    let mut free_claim_id = -1;
+    let mut free_claim_id = None;
 
     'claim_loop: for claim in claims.iter() {
         for w in 0..claim.width {
@@ -103,17 +106,16 @@ pub fn part2() {
                 }
             }
         }
-        free_claim_id = claim.id;
+        free_claim_id = Some(claim.id);
     }
 
-    println!(
-        "the ID of the only claim that doesn't overlap: {}",
-        free_claim_id
-    );
+    free_claim_id
+        .map(Answer::from)
+        .ok_or_else(|| Error::NoSolution("every claim overlaps another claim".into()))
 }
 
 #[derive(Debug)]
-struct FabricClaim {
+pub struct FabricClaim {
     id: i64,
     pos_x: i64,
     pos_y: i64,
@@ -122,21 +124,42 @@ struct FabricClaim {
 }
 
 impl FromStr for FabricClaim {
-    type Err = ParseIntError;
+    type Err = Error;
 
+    /// Parses a claim like `#123 @ 3,2: 5x4`
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let split: Vec<&str> = s.trim_start_matches('#').split('@').collect();
-        let id = split[0].trim().parse::<i64>()?;
-
-        let split: Vec<&str> = split[1].split(':').collect();
-
-        let pos: Vec<&str> = split[0].split(',').collect();
-        let pos_x = pos[0].trim().parse::<i64>()?;
-        let pos_y = pos[1].trim().parse::<i64>()?;
-
-        let size: Vec<&str> = split[1].split('x').collect();
-        let width = size[0].trim().parse::<i64>()?;
-        let height = size[1].trim().parse::<i64>()?;
+        fn split<'a>(
+            s: &str,
+            field: &'a str,
+            delimiter: char,
+            expected: &str,
+        ) -> Result<(&'a str, &'a str)> {
+            field
+                .split_once(delimiter)
+                .ok_or_else(|| Error::parse(s, field, format!("expected {}", expected)))
+        }
+        let number = |field: &str, name| {
+            let field = field.trim();
+            field
+                .parse::<i64>()
+                .map_err(|_| Error::parse(s, field, format!("invalid claim {} {:?}", name, field)))
+        };
+
+        let (id, claim) = split(
+            s,
+            s.trim_start_matches('#'),
+            '@',
+            "'#<id> @ <position>: <size>'",
+        )?;
+        let (pos, size) = split(s, claim, ':', "'<position>: <size>'")?;
+        let (pos_x, pos_y) = split(s, pos, ',', "'<x>,<y>'")?;
+        let (width, height) = split(s, size, 'x', "'<width>x<height>'")?;
+
+        let id = number(id, "id")?;
+        let pos_x = number(pos_x, "x position")?;
+        let pos_y = number(pos_y, "y position")?;
+        let width = number(width, "width")?;
+        let height = number(height, "height")?;
 
         Ok(FabricClaim {
             id,
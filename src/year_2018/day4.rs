@@ -2,8 +2,20 @@
 //!
 //! You've sneaked into another supply closet - this time, it's across from the prototype suit manufacturing lab. You need to sneak inside and fix the issues with the suit, but there's a guard stationed outside the lab, so this is as close as you can safely get.
 
+use crate::{Answer, Error, Result};
 use std::collections::BTreeMap;
 
+/// Parses every record in the puzzle input, in chronological order, as the minute it happened and what happened
+pub fn parse(input: &str) -> Result<Vec<(i64, Record)>> {
+    let mut sorted_input: Vec<_> = input.lines().enumerate().collect();
+    sorted_input.sort_by_key(|(_, line)| *line);
+
+    sorted_input
+        .into_iter()
+        .map(|(line_index, line)| parse_record(line).map_err(|error| error.on_line(line_index + 1)))
+        .collect()
+}
+
 /// As you search the closet for anything that might help, you discover that you're not the first person to want to sneak in. Covering the walls, someone has spent an hour starting every midnight for the past few months secretly observing this guard post! They've been writing down the ID of the one guard on duty that night - the Elves seem to have decided that one guard was enough for the overnight shift - as well as when they fall asleep or wake up while at their post (your puzzle input).
 ///
 /// For example, consider the following records, which have already been organized into chronological order:
@@ -52,13 +64,8 @@ use std::collections::BTreeMap;
 /// While this example listed the entries in chronological order, your entries are in the order you found them. You'll need to organize them before they can be analyzed.
 ///
 /// What is the ID of the guard you chose multiplied by the minute you chose? (In the above example, the answer would be 10 * 24 = 240.)
-pub fn part1() {
-    let input = crate::common::read_stdin_to_string();
-
-    let mut sorted_input: Vec<_> = input.lines().collect();
-    sorted_input.sort();
-
-    let sleep_schedule = build_part_1_sleep_schedule(sorted_input);
+pub fn part1(records: &[(i64, Record)]) -> Result<Answer> {
+    let sleep_schedule = build_part_1_sleep_schedule(records);
 
     let most_slept_guard = &sleep_schedule
         .iter()
@@ -71,8 +78,12 @@ pub fn part1() {
         })
         .1;
 
+    let (_, most_slept_guard_minutes) = sleep_schedule
+        .get(most_slept_guard)
+        .ok_or_else(|| Error::NoSolution("no guard ever falls asleep".into()))?;
+
     let mut slept_minutes: BTreeMap<i64, i64> = BTreeMap::new();
-    for minute in &sleep_schedule[&most_slept_guard].1 {
+    for minute in most_slept_guard_minutes {
         *slept_minutes.entry(*minute).or_insert(0) += 1;
     }
 
@@ -87,12 +98,7 @@ pub fn part1() {
         })
         .0;
 
-    println!(
-        "the id of the guard ({}) multiplied by the minute ({}): {}",
-        most_slept_guard,
-        most_slept_minute,
-        most_slept_guard * most_slept_minute
-    );
+    Ok((most_slept_guard * most_slept_minute).into())
 }
 
 /// Strategy 2: Of all guards, which guard is most frequently asleep on the same minute?
@@ -100,13 +106,8 @@ pub fn part1() {
 /// In the example above, Guard #99 spent minute 45 asleep more than any other guard or minute - three times in total. (In all other cases, any guard spent any minute asleep at most twice.)
 ///
 /// What is the ID of the guard you chose multiplied by the minute you chose? (In the above example, the answer would be 99 * 45 = 4455.)
-pub fn part2() {
-    let input = crate::common::read_stdin_to_string();
-
-    let mut sorted_input: Vec<_> = input.lines().collect();
-    sorted_input.sort();
-
-    let sleep_schedule = build_part_2_sleep_schedule(sorted_input);
+pub fn part2(records: &[(i64, Record)]) -> Result<Answer> {
+    let sleep_schedule = build_part_2_sleep_schedule(records);
 
     let most_slept_count_and_minute_and_guard =
         &sleep_schedule
@@ -125,46 +126,69 @@ pub fn part2() {
     let most_slept_minute = most_slept_count_and_minute_and_guard.1;
     let most_slept_guard = most_slept_count_and_minute_and_guard.2;
 
-    println!(
-        "the id of the guard ({}) multiplied by the minute ({}): {}",
-        most_slept_guard,
-        most_slept_minute,
-        most_slept_guard * most_slept_minute
-    );
+    Ok((most_slept_guard * most_slept_minute).into())
 }
 
-fn build_part_1_sleep_schedule<'a, T: IntoIterator<Item = &'a str>>(
-    sorted_input: T,
-) -> BTreeMap<i64, (i64, Vec<i64>)> {
+/// A single line of the guards' records, e.g. `[1518-11-01 00:00] Guard #10 begins shift`
+pub enum Record {
+    BeginsShift(i64),
+    FallsAsleep,
+    WakesUp,
+}
+
+/// Parses a record into the minute it happened at and what happened
+fn parse_record(line: &str) -> Result<(i64, Record)> {
+    let (timestamp, event) = line
+        .split_once("] ")
+        .ok_or_else(|| Error::parse(line, line, "expected '[<date> <time>] <event>'"))?;
+
+    let minute = timestamp
+        .get(15..17)
+        .and_then(|minute| minute.parse::<i64>().ok())
+        .ok_or_else(|| {
+            Error::parse(
+                line,
+                timestamp,
+                "expected a timestamp like '[1518-11-01 00:00'",
+            )
+        })?;
+
+    let record = match event {
+        "falls asleep" => Record::FallsAsleep,
+        "wakes up" => Record::WakesUp,
+        event => {
+            let guard = event
+                .strip_prefix("Guard #")
+                .and_then(|event| event.strip_suffix(" begins shift"))
+                .ok_or_else(|| Error::parse(line, event, format!("unknown event {:?}", event)))?;
+
+            Record::BeginsShift(
+                guard.parse().map_err(|_| {
+                    Error::parse(line, guard, format!("invalid guard id {:?}", guard))
+                })?,
+            )
+        }
+    };
+
+    Ok((minute, record))
+}
+
+fn build_part_1_sleep_schedule(records: &[(i64, Record)]) -> BTreeMap<i64, (i64, Vec<i64>)> {
     let mut sleep_schedule = BTreeMap::new();
     let mut guard = 0;
     let mut last_minute = 0;
 
-    for line in sorted_input {
-        let minute = line
-            .chars()
-            .skip(15)
-            .take(2)
-            .collect::<String>()
-            .parse::<i64>()
-            .unwrap();
-
-        match line.split(' ').nth(2).unwrap() {
-            "Guard" => {
-                guard = line
-                    .split(' ')
-                    .nth(3)
-                    .unwrap()
-                    .chars()
-                    .skip(1)
-                    .collect::<String>()
-                    .parse::<i64>()
-                    .unwrap();
+    for (minute, record) in records {
+        let minute = *minute;
+
+        match record {
+            Record::BeginsShift(id) => {
+                guard = *id;
             }
-            "falls" => {
+            Record::FallsAsleep => {
                 last_minute = minute;
             }
-            "wakes" => {
+            Record::WakesUp => {
                 let entry = sleep_schedule.entry(guard).or_insert((0, Vec::new()));
                 let mut minutes_slept = minute - last_minute;
                 while minutes_slept < 0 {
@@ -176,46 +200,29 @@ fn build_part_1_sleep_schedule<'a, T: IntoIterator<Item = &'a str>>(
                 }
                 last_minute = minute;
             }
-            _ => panic!("unhandled input: {}", line),
         }
     }
 
     sleep_schedule
 }
 
-fn build_part_2_sleep_schedule<'a, T: IntoIterator<Item = &'a str>>(
-    sorted_input: T,
-) -> BTreeMap<i64, BTreeMap<i64, i64>> {
-    let mut sleep_schedule = BTreeMap::new();
+fn build_part_2_sleep_schedule(records: &[(i64, Record)]) -> BTreeMap<i64, BTreeMap<i64, i64>> {
+    let mut sleep_schedule: BTreeMap<i64, BTreeMap<i64, i64>> = BTreeMap::new();
     let mut guard = 0;
     let mut last_minute = 0;
 
-    for line in sorted_input {
-        let minute = line
-            .chars()
-            .skip(15)
-            .take(2)
-            .collect::<String>()
-            .parse::<i64>()
-            .unwrap();
-
-        match line.split(' ').nth(2).unwrap() {
-            "Guard" => {
-                guard = line
-                    .split(' ')
-                    .nth(3)
-                    .unwrap()
-                    .chars()
-                    .skip(1)
-                    .collect::<String>()
-                    .parse::<i64>()
-                    .unwrap();
+    for (minute, record) in records {
+        let minute = *minute;
+
+        match record {
+            Record::BeginsShift(id) => {
+                guard = *id;
             }
-            "falls" => {
+            Record::FallsAsleep => {
                 last_minute = minute;
             }
-            "wakes" => {
-                let guard_entry = sleep_schedule.entry(guard).or_insert_with(BTreeMap::new);
+            Record::WakesUp => {
+                let guard_entry = sleep_schedule.entry(guard).or_default();
                 let mut minutes_slept = minute - last_minute;
                 while minutes_slept < 0 {
                     minutes_slept = 60 - minutes_slept;
@@ -227,7 +234,6 @@ fn build_part_2_sleep_schedule<'a, T: IntoIterator<Item = &'a str>>(
 
                 last_minute = minute;
             }
-            _ => panic!("unhandled input: {}", line),
         }
     }
 
@@ -2,16 +2,25 @@
 //!
 //! You've managed to sneak in to the prototype suit manufacturing lab. The Elves are making decent progress, but are still struggling with the suit's size reduction capabilities.
 
+use crate::{Answer, Result};
+
+/// Parses the polymer in the puzzle input into its units
+pub fn parse(input: &str) -> Result<Vec<char>> {
+    Ok(input.trim().chars().collect())
+}
+
 /// While the very latest in 1518 alchemical technology might have solved their problem eventually, you can do better. You scan the chemical composition of the suit's material and discover that it is formed by extremely long polymers (one of which is available as your puzzle input).
 ///
 /// The polymer is formed by smaller units which, when triggered, react with each other such that two adjacent units of the same type and opposite polarity are destroyed. Units' types are represented by letters; units' polarity is represented by capitalization. For instance, r and R are units with the same type but opposite polarity, whereas r and s are entirely different types and do not react.
 ///
 /// For example:
 ///
-///     In aA, a and A react, leaving nothing behind.
-///     In abBA, bB destroys itself, leaving aA. As above, this then destroys itself, leaving nothing.
-///     In abAB, no two adjacent units are of the same type, and so nothing happens.
-///     In aabAAB, even though aa and AA are of the same type, their polarities match, and so nothing happens.
+/// ```text
+/// In aA, a and A react, leaving nothing behind.
+/// In abBA, bB destroys itself, leaving aA. As above, this then destroys itself, leaving nothing.
+/// In abAB, no two adjacent units are of the same type, and so nothing happens.
+/// In aabAAB, even though aa and AA are of the same type, their polarities match, and so nothing happens.
+/// ```
 ///
 /// Now, consider a larger example, dabAcCaCBAcCcaDA:
 ///
@@ -23,19 +32,14 @@
 /// After all possible reactions, the resulting polymer contains 10 units.
 ///
 /// How many units remain after fully reacting the polymer you scanned?
-pub fn part1() {
-    let input = crate::common::read_stdin_to_string();
-
-    let mut polymer: Vec<_> = input.trim().chars().collect();
+pub fn part1(polymer: &[char]) -> Result<Answer> {
+    let mut polymer = polymer.to_vec();
 
     react_polymer(&mut polymer, None);
 
     let number_of_units = polymer.len();
 
-    println!(
-        "the number of units remaining after fully reacting the polymer you scanned: {}",
-        number_of_units
-    );
+    Ok(number_of_units.into())
 }
 
 /// Time to improve the polymer.
@@ -44,18 +48,17 @@ pub fn part1() {
 ///
 /// For example, again using the polymer dabAcCaCBAcCcaDA from above:
 ///
-///     Removing all A/a units produces dbcCCBcCcD. Fully reacting this polymer produces dbCBcD, which has length 6.
-///     Removing all B/b units produces daAcCaCAcCcaDA. Fully reacting this polymer produces daCAcaDA, which has length 8.
-///     Removing all C/c units produces dabAaBAaDA. Fully reacting this polymer produces daDA, which has length 4.
-///     Removing all D/d units produces abAcCaCBAcCcaA. Fully reacting this polymer produces abCBAc, which has length 6.
+/// ```text
+/// Removing all A/a units produces dbcCCBcCcD. Fully reacting this polymer produces dbCBcD, which has length 6.
+/// Removing all B/b units produces daAcCaCAcCcaDA. Fully reacting this polymer produces daCAcaDA, which has length 8.
+/// Removing all C/c units produces dabAaBAaDA. Fully reacting this polymer produces daDA, which has length 4.
+/// Removing all D/d units produces abAcCaCBAcCcaA. Fully reacting this polymer produces abCBAc, which has length 6.
+/// ```
 ///
 /// In this example, removing all C/c units was best, producing the answer 4.
 ///
 /// What is the length of the shortest polymer you can produce by removing all units of exactly one type and fully reacting the result?
-pub fn part2() {
-    let input = crate::common::read_stdin_to_string();
-
-    let polymer: Vec<_> = input.trim().chars().collect();
+pub fn part2(polymer: &[char]) -> Result<Answer> {
     let mut shortest_polymer = polymer.len();
 
     let drop_units = [
@@ -95,7 +98,7 @@ pub fn part2() {
         }
     }
 
-    println!("the length of the shortest polymer: {}", shortest_polymer);
+    Ok(shortest_polymer.into())
 }
 
 fn react_polymer<T: Into<Option<(char, char)>>>(polymer: &mut Vec<char>, drop_unit: T) {
@@ -110,9 +113,7 @@ fn react_polymer<T: Into<Option<(char, char)>>>(polymer: &mut Vec<char>, drop_un
             if unit == drop_unit.0 || unit == drop_unit.1 {
                 polymer.remove(i);
 
-                if i != 0 {
-                    i -= 1;
-                }
+                i = i.saturating_sub(1);
                 continue;
             }
             if next_unit == drop_unit.0 || next_unit == drop_unit.1 {
@@ -125,9 +126,7 @@ fn react_polymer<T: Into<Option<(char, char)>>>(polymer: &mut Vec<char>, drop_un
             polymer.remove(i);
             polymer.remove(i);
 
-            if i != 0 {
-                i -= 1;
-            }
+            i = i.saturating_sub(1);
             continue;
         }
 
@@ -2,9 +2,9 @@
 //!
 //! The device on your wrist beeps several times, and once again you feel like you're falling.
 
+use crate::{Answer, Error, Result};
 use std::cmp;
 use std::collections::BTreeMap;
-use std::i64;
 
 /// "Situation critical," the device announces. "Destination indeterminate. Chronal interference detected. Please specify new target coordinates."
 ///
@@ -54,19 +54,13 @@ use std::i64;
 /// In this example, the areas of coordinates A, B, C, and F are infinite - while not shown here, their areas extend forever outside the visible grid. However, the areas of coordinates D and E are finite: D is closest to 9 locations, and E is closest to 17 (both including the coordinate's location itself). Therefore, in this example, the size of the largest area is 17.
 ///
 /// What is the size of the largest area that isn't infinite?
-pub fn part1() {
-    let input = crate::common::read_stdin_to_string();
-
-    let coords = input_to_coords(&input);
-    let bounds = get_bounds(&coords);
-    let areas = calculate_areas(&coords, bounds);
+pub fn part1(coords: &[(i64, i64)]) -> Result<Answer> {
+    let bounds = get_bounds(coords);
+    let areas = calculate_areas(coords, bounds);
 
     let largest_area = areas.values().fold(0, |acc, v| cmp::max(acc, *v));
 
-    println!(
-        "the size of the largest area that isn't infinite: {}",
-        largest_area
-    );
+    Ok(largest_area.into())
 }
 
 /// On the other hand, if the coordinates are safe, maybe the best you can do is try to find a region near as many coordinates as possible.
@@ -86,13 +80,15 @@ pub fn part1() {
 ///
 /// In particular, consider the highlighted location 4,3 located at the top middle of the region. Its calculation is as follows, where abs() is the absolute value function:
 ///
-///     Distance to coordinate A: abs(4-1) + abs(3-1) =  5
-///     Distance to coordinate B: abs(4-1) + abs(3-6) =  6
-///     Distance to coordinate C: abs(4-8) + abs(3-3) =  4
-///     Distance to coordinate D: abs(4-3) + abs(3-4) =  2
-///     Distance to coordinate E: abs(4-5) + abs(3-5) =  3
-///     Distance to coordinate F: abs(4-8) + abs(3-9) = 10
-///     Total distance: 5 + 6 + 4 + 2 + 3 + 10 = 30
+/// ```text
+/// Distance to coordinate A: abs(4-1) + abs(3-1) =  5
+/// Distance to coordinate B: abs(4-1) + abs(3-6) =  6
+/// Distance to coordinate C: abs(4-8) + abs(3-3) =  4
+/// Distance to coordinate D: abs(4-3) + abs(3-4) =  2
+/// Distance to coordinate E: abs(4-5) + abs(3-5) =  3
+/// Distance to coordinate F: abs(4-8) + abs(3-9) = 10
+/// Total distance: 5 + 6 + 4 + 2 + 3 + 10 = 30
+/// ```
 ///
 /// Because the total distance to all coordinates (30) is less than 32, the location is within the region.
 ///
@@ -101,28 +97,29 @@ pub fn part1() {
 /// Your actual region will need to be much larger than this example, though, instead including all locations with a total distance of less than 10000.
 ///
 /// What is the size of the region containing all locations which have a total distance to all given coordinates of less than 10000?
-pub fn part2() {
-    let input = crate::common::read_stdin_to_string();
-
-    let coords = input_to_coords(&input);
-    let bounds = get_bounds(&coords);
-    let region_size = calculate_region_size(&coords, bounds);
+pub fn part2(coords: &[(i64, i64)]) -> Result<Answer> {
+    let bounds = get_bounds(coords);
+    let region_size = calculate_region_size(coords, bounds);
 
-    println!(
-        "the size of the region containing all locations which have a total distance to all given coordinates of less than 10000: {}",
-        region_size
-    );
+    Ok(region_size.into())
 }
 
-fn input_to_coords(input: &str) -> Vec<(i64, i64)> {
+/// Parses the list of coordinates in the puzzle input
+pub fn parse(input: &str) -> Result<Vec<(i64, i64)>> {
+    let number = |field: &str| {
+        let field = field.trim();
+        field
+            .parse()
+            .map_err(|_| Error::parse(input, field, format!("invalid coordinate {:?}", field)))
+    };
+
     input
         .lines()
         .map(|line| {
-            let split: Vec<_> = line.split(',').collect();
-            (
-                split[0].trim().parse().unwrap(),
-                split[1].trim().parse().unwrap(),
-            )
+            let (x, y) = line
+                .split_once(',')
+                .ok_or_else(|| Error::parse(input, line, "expected a coordinate like '1, 6'"))?;
+            Ok((number(x)?, number(y)?))
         })
         .collect()
 }
@@ -204,3 +201,20 @@ fn calculate_region_size(
 fn taxicab_distance(a: (i64, i64), b: (i64, i64)) -> i64 {
     (cmp::max(a.0, b.0) - cmp::min(a.0, b.0)) + (cmp::max(a.1, b.1) - cmp::min(a.1, b.1))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_coordinates() {
+        assert_eq!(
+            parse("1, 1\n1 6").unwrap_err().to_string(),
+            "invalid input at line 2, column 1: expected a coordinate like '1, 6'"
+        );
+        assert_eq!(
+            parse("1, 1\n1, x").unwrap_err().to_string(),
+            "invalid input at line 2, column 4: invalid coordinate \"x\""
+        );
+    }
+}
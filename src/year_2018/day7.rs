@@ -2,6 +2,7 @@
 //!
 //! You find yourself standing on a snow-covered coastline; apparently, you landed a little off course. The region is too hilly to see the North Pole from here, but you do spot some Elves that seem to be trying to unpack something that washed ashore. It's quite cold out, so you decide to risk creating a paradox by asking them for directions.
 
+use crate::{Answer, Error, Result};
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 
@@ -32,20 +33,19 @@ use std::collections::BTreeSet;
 ///
 /// Your first goal is to determine the order in which the steps should be completed. If more than one step is ready, choose the step which is first alphabetically. In this example, the steps would be completed as follows:
 ///
-///     Only C is available, and so it is done first.
-///     Next, both A and F are available. A is first alphabetically, so it is done next.
-///     Then, even though F was available earlier, steps B and D are now also available, and B is the first alphabetically of the three.
-///     After that, only D and F are available. E is not available because only some of its prerequisites are complete. Therefore, D is completed next.
-///     F is the only choice, so it is done next.
-///     Finally, E is completed.
+/// ```text
+/// Only C is available, and so it is done first.
+/// Next, both A and F are available. A is first alphabetically, so it is done next.
+/// Then, even though F was available earlier, steps B and D are now also available, and B is the first alphabetically of the three.
+/// After that, only D and F are available. E is not available because only some of its prerequisites are complete. Therefore, D is completed next.
+/// F is the only choice, so it is done next.
+/// Finally, E is completed.
+/// ```
 ///
 /// So, in this example, the correct order is CABDFE.
 ///
 /// In what order should the steps in your instructions be completed?
-pub fn part1() {
-    let input = crate::common::read_stdin_to_string();
-
-    let instructions = input_to_instructions(input);
+pub fn part1(instructions: &[(char, char)]) -> Result<Answer> {
     let mut step_graph = StepGraph::from_instructions(instructions);
 
     let mut steps = Vec::new();
@@ -53,10 +53,7 @@ pub fn part1() {
         steps.push(step);
     }
 
-    println!(
-        "the order the steps should be completed: {}",
-        steps.iter().collect::<String>()
-    );
+    Ok(steps.iter().collect::<String>().into())
 }
 
 /// As you're about to begin construction, four of the Elves offer to help. "The sun will set soon; it'll go faster if we work together." Now, you need to account for multiple people working on steps simultaneously. If multiple steps are available, workers should still begin them in alphabetical order.
@@ -90,10 +87,7 @@ pub fn part1() {
 /// In this example, it would take 15 seconds for two workers to complete these steps.
 ///
 /// With 5 workers and the 60+ second step durations described above, how long will it take to complete all of the steps?
-pub fn part2() {
-    let input = crate::common::read_stdin_to_string();
-
-    let instructions = input_to_instructions(input);
+pub fn part2(instructions: &[(char, char)]) -> Result<Answer> {
     let mut step_graph = StepGraph::from_instructions(instructions);
 
     let mut seconds = 0;
@@ -142,19 +136,35 @@ pub fn part2() {
             .collect();
     }
 
-    println!(
-        "seconds it will take to complete all of the steps: {}",
-        seconds
-    );
+    Ok(seconds.into())
 }
 
-fn input_to_instructions(input: String) -> Vec<(char, char)> {
+/// Parses lines like `Step C must be finished before step A can begin.`
+pub fn parse(input: &str) -> Result<Vec<(char, char)>> {
+    let parse_step = |step: Option<&str>, line: &str| match step {
+        Some(step) if step.len() == 1 && step.chars().all(|c| c.is_ascii_uppercase()) => {
+            Ok(step.chars().next().unwrap_or_default())
+        }
+        Some(step) => Err(Error::parse(
+            input,
+            step,
+            format!("invalid step {:?}", step),
+        )),
+        None => Err(Error::parse(
+            input,
+            line,
+            "expected 'Step <A> must be finished before step <B> can begin.'",
+        )),
+    };
+
     let mut instructions = Vec::new();
     for line in input.lines() {
-        let split: Vec<_> = line.split(' ').collect();
-        instructions.push((split[1].parse().unwrap(), split[7].parse().unwrap()));
+        let mut split = line.split(' ');
+        let step = parse_step(split.nth(1), line)?;
+        let next_step = parse_step(split.nth(5), line)?;
+        instructions.push((step, next_step));
     }
-    instructions
+    Ok(instructions)
 }
 
 #[derive(Debug)]
@@ -173,7 +183,7 @@ impl StepGraph {
         }
     }
 
-    fn from_instructions(instructions: Vec<(char, char)>) -> StepGraph {
+    fn from_instructions(instructions: &[(char, char)]) -> StepGraph {
         let mut step_graph = StepGraph::new();
         let mut all_steps = BTreeSet::new();
         for instruction in instructions.iter() {
@@ -182,7 +192,7 @@ impl StepGraph {
             step_graph.add_step_dependency(instruction.0, instruction.1);
         }
         for step in all_steps.iter() {
-            if !step_graph.step_dependencies.contains_key(&step) {
+            if !step_graph.step_dependencies.contains_key(step) {
                 step_graph.available_steps.push(*step);
             }
         }
@@ -190,10 +200,7 @@ impl StepGraph {
     }
 
     fn add_step_dependency(&mut self, from: char, to: char) {
-        self.step_dependencies
-            .entry(to)
-            .or_insert_with(Vec::new)
-            .push(from);
+        self.step_dependencies.entry(to).or_default().push(from);
     }
 
     fn next_step(&mut self, complete_step: bool) -> Option<char> {
@@ -203,10 +210,7 @@ impl StepGraph {
 
         self.available_steps.sort_unstable_by(|a, b| b.cmp(a));
 
-        let next_step = match self.available_steps.pop() {
-            Some(step) => step,
-            None => return None,
-        };
+        let next_step = self.available_steps.pop()?;
 
         if complete_step {
             self.complete_step(next_step);
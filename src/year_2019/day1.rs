@@ -1,5 +1,7 @@
 //! --- Day 1: The Tyranny of the Rocket Equation ---
 
+use crate::{Answer, Error, Result};
+
 /// The Elves quickly load you into a spacecraft and prepare to launch.
 ///
 /// At the first Go / No Go poll, every Elf is Go until the Fuel Counter-Upper. They haven't determined the amount of fuel required yet.
@@ -8,27 +10,24 @@
 ///
 /// For example:
 ///
-///     For a mass of 12, divide by 3 and round down to get 4, then subtract 2 to get 2.
-///     For a mass of 14, dividing by 3 and rounding down still yields 4, so the fuel required is also 2.
-///     For a mass of 1969, the fuel required is 654.
-///     For a mass of 100756, the fuel required is 33583.
+/// ```text
+/// For a mass of 12, divide by 3 and round down to get 4, then subtract 2 to get 2.
+/// For a mass of 14, dividing by 3 and rounding down still yields 4, so the fuel required is also 2.
+/// For a mass of 1969, the fuel required is 654.
+/// For a mass of 100756, the fuel required is 33583.
+/// ```
 ///
 /// The Fuel Counter-Upper needs to know the total fuel requirement. To find it, individually calculate the fuel needed for the mass of each module (your puzzle input), then add together all the fuel values.
 ///
 /// What is the sum of the fuel requirements for all of the modules on your spacecraft?
-pub fn part1() {
-    let input = crate::common::read_stdin_to_string();
-
-    let fuel_required: f64 = input
-        .lines()
-        .map(|line| line.parse::<f64>().expect("Failed to parse line as f64"))
+pub fn part1(masses: &[i64]) -> Result<Answer> {
+    let fuel_required: i64 = masses
+        .iter()
+        .copied()
         .map(simple_fuel_required_for_mass)
         .sum();
 
-    println!(
-        "The sum of the fuel requirements for all of the modules on your spacecraft: {} units",
-        fuel_required
-    );
+    Ok(fuel_required.into())
 }
 
 /// During the second Go / No Go poll, the Elf in charge of the Rocket Equation Double-Checker stops the launch sequence. Apparently, you forgot to include additional fuel for the fuel you just added.
@@ -37,36 +36,86 @@ pub fn part1() {
 ///
 /// So, for each module mass, calculate its fuel and add it to the total. Then, treat the fuel amount you just calculated as the input mass and repeat the process, continuing until a fuel requirement is zero or negative. For example:
 ///
-///     A module of mass 14 requires 2 fuel. This fuel requires no further fuel (2 divided by 3 and rounded down is 0, which would call for a negative fuel), so the total fuel required is still just 2.
-///     At first, a module of mass 1969 requires 654 fuel. Then, this fuel requires 216 more fuel (654 / 3 - 2). 216 then requires 70 more fuel, which requires 21 fuel, which requires 5 fuel, which requires no further fuel. So, the total fuel required for a module of mass 1969 is 654 + 216 + 70 + 21 + 5 = 966.
-///     The fuel required by a module of mass 100756 and its fuel is: 33583 + 11192 + 3728 + 1240 + 411 + 135 + 43 + 12 + 2 = 50346.
+/// ```text
+/// A module of mass 14 requires 2 fuel. This fuel requires no further fuel (2 divided by 3 and rounded down is 0, which would call for a negative fuel), so the total fuel required is still just 2.
+/// At first, a module of mass 1969 requires 654 fuel. Then, this fuel requires 216 more fuel (654 / 3 - 2). 216 then requires 70 more fuel, which requires 21 fuel, which requires 5 fuel, which requires no further fuel. So, the total fuel required for a module of mass 1969 is 654 + 216 + 70 + 21 + 5 = 966.
+/// The fuel required by a module of mass 100756 and its fuel is: 33583 + 11192 + 3728 + 1240 + 411 + 135 + 43 + 12 + 2 = 50346.
+/// ```
 ///
 /// What is the sum of the fuel requirements for all of the modules on your spacecraft when also taking into account the mass of the added fuel? (Calculate the fuel requirements for each module separately, then add them all up at the end.)
-pub fn part2() {
-    let input = crate::common::read_stdin_to_string();
-
-    let fuel_required: f64 = input
-        .lines()
-        .map(|line| line.parse::<f64>().expect("Failed to parse line as f64"))
+pub fn part2(masses: &[i64]) -> Result<Answer> {
+    let fuel_required: i64 = masses
+        .iter()
+        .copied()
         .map(recursive_fuel_required_for_mass)
         .sum();
 
-    println!(
-        "The sum of the fuel requirements for all of the modules on your spacecraft when also taking into account the mass of the added fuel: {}",
-        fuel_required
-    );
+    Ok(fuel_required.into())
 }
 
-fn simple_fuel_required_for_mass(mass: f64) -> f64 {
-    f64::max(0.0, (mass / 3.0).trunc() - 2.0)
+/// Parses the module masses in the puzzle input
+pub fn parse(input: &str) -> Result<Vec<i64>> {
+    input
+        .lines()
+        .map(|line| {
+            line.trim()
+                .parse()
+                .map_err(|_| Error::parse(input, line, format!("invalid module mass {:?}", line)))
+        })
+        .collect()
+}
+
+fn simple_fuel_required_for_mass(mass: i64) -> i64 {
+    i64::max(0, mass / 3 - 2)
 }
 
-fn recursive_fuel_required_for_mass(mass: f64) -> f64 {
+fn recursive_fuel_required_for_mass(mass: i64) -> i64 {
     let fuel_required = simple_fuel_required_for_mass(mass);
 
-    if fuel_required > 0.0 {
+    if fuel_required > 0 {
         return fuel_required + recursive_fuel_required_for_mass(fuel_required);
     }
 
     fuel_required
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part1_examples() {
+        let examples = [("12", 2), ("14", 2), ("1969", 654), ("100756", 33583)];
+
+        for example in &examples {
+            assert_eq!(
+                part1(&parse(example.0).unwrap()).unwrap(),
+                Answer::Integer(example.1)
+            );
+        }
+    }
+
+    #[test]
+    fn test_part2_examples() {
+        let examples = [("14", 2), ("1969", 966), ("100756", 50346)];
+
+        for example in &examples {
+            assert_eq!(
+                part2(&parse(example.0).unwrap()).unwrap(),
+                Answer::Integer(example.1)
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_masses() {
+        assert_eq!(
+            parse("12\n1x").unwrap_err().to_string(),
+            "invalid input at line 2, column 1: invalid module mass \"1x\""
+        );
+        assert_eq!(
+            parse("-").unwrap_err().to_string(),
+            "invalid input at line 1, column 1: invalid module mass \"-\""
+        );
+    }
+}
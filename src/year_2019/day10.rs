@@ -1,5 +1,22 @@
 //! --- Day 10: Monitoring Station ---
 
+use crate::{Answer, Error, Result};
+use std::convert::TryFrom;
+
+/// Parses the map of asteroids in the puzzle input
+pub fn parse(input: &str) -> Result<AsteroidMap> {
+    let map = AsteroidMap::try_from(input)?;
+    if map.0.is_empty() {
+        return Err(Error::parse(
+            input,
+            input.trim(),
+            "the map has no asteroids",
+        ));
+    }
+
+    Ok(map)
+}
+
 /// You fly into the asteroid belt and reach the Ceres monitoring station. The Elves here have an emergency: they're having trouble tracking all of the asteroids and can't be sure they're safe.
 ///
 /// The Elves would like to build a new monitoring station in a nearby area of space; they hand you a map of all of the asteroids in that region (your puzzle input).
@@ -39,87 +56,84 @@
 ///
 /// Here are some larger examples:
 ///
-///     Best is 5,8 with 33 other asteroids detected:
+/// ```text
+/// Best is 5,8 with 33 other asteroids detected:
 ///
-///     ......#.#.
-///     #..#.#....
-///     ..#######.
-///     .#.#.###..
-///     .#..#.....
-///     ..#....#.#
-///     #..#....#.
-///     .##.#..###
-///     ##...#..#.
-///     .#....####
+/// ......#.#.
+/// #..#.#....
+/// ..#######.
+/// .#.#.###..
+/// .#..#.....
+/// ..#....#.#
+/// #..#....#.
+/// .##.#..###
+/// ##...#..#.
+/// .#....####
 ///
-///     Best is 1,2 with 35 other asteroids detected:
+/// Best is 1,2 with 35 other asteroids detected:
 ///
-///     #.#...#.#.
-///     .###....#.
-///     .#....#...
-///     ##.#.#.#.#
-///     ....#.#.#.
-///     .##..###.#
-///     ..#...##..
-///     ..##....##
-///     ......#...
-///     .####.###.
+/// #.#...#.#.
+/// .###....#.
+/// .#....#...
+/// ##.#.#.#.#
+/// ....#.#.#.
+/// .##..###.#
+/// ..#...##..
+/// ..##....##
+/// ......#...
+/// .####.###.
 ///
-///     Best is 6,3 with 41 other asteroids detected:
+/// Best is 6,3 with 41 other asteroids detected:
 ///
-///     .#..#..###
-///     ####.###.#
-///     ....###.#.
-///     ..###.##.#
-///     ##.##.#.#.
-///     ....###..#
-///     ..#.#..#.#
-///     #..#.#.###
-///     .##...##.#
-///     .....#.#..
+/// .#..#..###
+/// ####.###.#
+/// ....###.#.
+/// ..###.##.#
+/// ##.##.#.#.
+/// ....###..#
+/// ..#.#..#.#
+/// #..#.#.###
+/// .##...##.#
+/// .....#.#..
 ///
-///     Best is 11,13 with 210 other asteroids detected:
+/// Best is 11,13 with 210 other asteroids detected:
 ///
-///     .#..##.###...#######
-///     ##.############..##.
-///     .#.######.########.#
-///     .###.#######.####.#.
-///     #####.##.#.##.###.##
-///     ..#####..#.#########
-///     ####################
-///     #.####....###.#.#.##
-///     ##.#################
-///     #####.##.###..####..
-///     ..######..##.#######
-///     ####.##.####...##..#
-///     .#####..#.######.###
-///     ##...#.##########...
-///     #.##########.#######
-///     .####.#.###.###.#.##
-///     ....##.##.###..#####
-///     .#.#.###########.###
-///     #.#.#.#####.####.###
-///     ###.##.####.##.#..##
+/// .#..##.###...#######
+/// ##.############..##.
+/// .#.######.########.#
+/// .###.#######.####.#.
+/// #####.##.#.##.###.##
+/// ..#####..#.#########
+/// ####################
+/// #.####....###.#.#.##
+/// ##.#################
+/// #####.##.###..####..
+/// ..######..##.#######
+/// ####.##.####...##..#
+/// .#####..#.######.###
+/// ##...#.##########...
+/// #.##########.#######
+/// .####.#.###.###.#.##
+/// ....##.##.###..#####
+/// .#.#.###########.###
+/// #.#.#.#####.####.###
+/// ###.##.####.##.#..##
+/// ```
 ///
 /// Find the best location for a new monitoring station. How many other asteroids can be detected from that location?
+pub fn part1(map: &AsteroidMap) -> Result<Answer> {
+    let (number_of_asteroids, _) = map
+        .calculate_best_monitoring_station()
+        .ok_or_else(|| Error::NoSolution("the map has no asteroids".into()))?;
 
-pub fn part1() {
-    let input = crate::common::read_stdin_to_string();
-
-    let map = AsteroidMap::from(input.as_str());
-    let (number_of_asteroids, _) = map.calculate_best_monitoring_station();
-
-    println!(
-        "The number of other asteroids that can be detected from the best location for a new monitoring station: {}",
-        number_of_asteroids
-    );
+    Ok(number_of_asteroids.into())
 }
 
 #[derive(Debug)]
-struct AsteroidMap(Vec<Point>);
+pub struct AsteroidMap(Vec<Point>);
 
 impl AsteroidMap {
-    fn calculate_best_monitoring_station(&self) -> (usize, Point) {
+    fn calculate_best_monitoring_station(&self) -> Option<(usize, Point)> {
         self.0
             .iter()
             .copied()
@@ -127,7 +141,6 @@ impl AsteroidMap {
             .max_by(|(visible_asteroids_one, _), (visible_asteroids_two, _)| {
                 visible_asteroids_one.cmp(visible_asteroids_two)
             })
-            .expect("Failed to find best monitoring station")
     }
 
     fn number_of_visible_asteroids(&self, from: Point) -> usize {
@@ -146,24 +159,32 @@ impl AsteroidMap {
     }
 }
 
-impl From<&str> for AsteroidMap {
-    fn from(string: &str) -> Self {
-        Self(
-            string
-                .trim()
-                .split('\n')
-                .enumerate()
-                .flat_map(|(y, line)| {
-                    line.chars()
-                        .enumerate()
-                        .filter(|(_, character)| *character == '#')
-                        .map(move |(x, _)| Point {
-                            x: x as i64,
-                            y: y as i64,
-                        })
-                })
-                .collect::<Vec<_>>(),
-        )
+impl TryFrom<&str> for AsteroidMap {
+    type Error = Error;
+
+    fn try_from(string: &str) -> Result<Self> {
+        let mut asteroids = Vec::new();
+
+        for (y, line) in string.trim().lines().enumerate() {
+            for (x, (index, character)) in line.char_indices().enumerate() {
+                match character {
+                    '#' => asteroids.push(Point {
+                        x: x as i64,
+                        y: y as i64,
+                    }),
+                    '.' => {}
+                    _ => {
+                        return Err(Error::parse(
+                            string,
+                            &line[index..],
+                            format!("expected '#' or '.', found {:?}", character),
+                        ))
+                    }
+                }
+            }
+        }
+
+        Ok(Self(asteroids))
     }
 }
 
@@ -304,9 +325,24 @@ mod tests {
         ];
 
         for example in &examples {
-            let best_station = AsteroidMap::from(example.0).calculate_best_monitoring_station();
+            let best_station = AsteroidMap::try_from(example.0)
+                .unwrap()
+                .calculate_best_monitoring_station()
+                .unwrap();
             assert_eq!(best_station.1, example.1);
             assert_eq!(best_station.0, example.2);
         }
     }
+
+    #[test]
+    fn test_invalid_map() {
+        assert_eq!(
+            parse(".#\n#x").unwrap_err().to_string(),
+            "invalid input at line 2, column 2: expected '#' or '.', found 'x'"
+        );
+        assert_eq!(
+            parse("..\n..").unwrap_err().to_string(),
+            "invalid input at line 1, column 1: the map has no asteroids"
+        );
+    }
 }
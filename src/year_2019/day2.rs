@@ -1,6 +1,12 @@
 //! --- Day 2: 1202 Program Alarm ---
 
 use super::{IntcodeComputer, IntcodeProgram};
+use crate::{Answer, Error, Result};
+
+/// Parses the Intcode program in the puzzle input
+pub fn parse(input: &str) -> Result<IntcodeProgram> {
+    input.parse()
+}
 
 /// On the way to your gravity assist around the Moon, your ship computer beeps angrily about a "1202 program alarm". On the radio, an Elf is already explaining how to handle the situation: "Don't worry, that's perfectly norma--" The ship computer bursts into flames.
 ///
@@ -45,26 +51,24 @@ use super::{IntcodeComputer, IntcodeProgram};
 ///
 /// Here are the initial and final states of a few more small programs:
 ///
-///     1,0,0,0,99 becomes 2,0,0,0,99 (1 + 1 = 2).
-///     2,3,0,3,99 becomes 2,3,0,6,99 (3 * 2 = 6).
-///     2,4,4,5,99,0 becomes 2,4,4,5,99,9801 (99 * 99 = 9801).
-///     1,1,1,4,99,5,6,0,99 becomes 30,1,1,4,2,5,6,0,99.
+/// ```text
+/// 1,0,0,0,99 becomes 2,0,0,0,99 (1 + 1 = 2).
+/// 2,3,0,3,99 becomes 2,3,0,6,99 (3 * 2 = 6).
+/// 2,4,4,5,99,0 becomes 2,4,4,5,99,9801 (99 * 99 = 9801).
+/// 1,1,1,4,99,5,6,0,99 becomes 30,1,1,4,2,5,6,0,99.
+/// ```
 ///
 /// Once you have a working computer, the first step is to restore the gravity assist program (your puzzle input) to the "1202 program alarm" state it had just before the last computer caught fire. To do this, before running the program, replace position 1 with the value 12 and replace position 2 with the value 2. What value is left at position 0 after the program halts?
-pub fn part1() {
-    let input = crate::common::read_stdin_to_string();
-    let mut computer = IntcodeComputer::from(input.as_str());
+pub fn part1(program: &IntcodeProgram) -> Result<Answer> {
+    let mut computer = IntcodeComputer::from(program);
 
     // restore the gravity assist program to the "1202 program alarm" state
     computer.memory.replace(1, 12);
     computer.memory.replace(2, 2);
 
-    computer.run();
+    computer.run()?;
 
-    println!(
-        "The value left at position 0 after the program halts: {}",
-        computer.memory.get(0)
-    );
+    Ok(computer.memory.get(0).into())
 }
 
 /// "Good, the new computer seems to be working correctly! Keep it nearby during this mission - you'll probably use it again. Real Intcode computers support many more features than your new one, but we'll let you know what they are as you need them."
@@ -84,42 +88,34 @@ pub fn part1() {
 /// Once the program has halted, its output is available at address 0, also just like before. Each time you try a pair of inputs, make sure you first reset the computer's memory to the values in the program (your puzzle input) - in other words, don't reuse memory from a previous attempt.
 ///
 /// Find the input noun and verb that cause the program to produce the output 19690720. What is 100 * noun + verb? (For example, if noun=12 and verb=2, the answer would be 1202.)
-pub fn part2() {
-    let input = crate::common::read_stdin_to_string();
-    let program = IntcodeProgram::from(input.as_str());
-    let mut computer = IntcodeComputer::from(&program);
+pub fn part2(program: &IntcodeProgram) -> Result<Answer> {
+    let mut computer = IntcodeComputer::from(program);
 
     const DESIRED_OUTPUT: i64 = 19_690_720;
 
-    let mut result = None;
-    'outer: for noun in 0..100 {
+    // a noun and verb could send the program into an infinite loop, when it should run for a few dozen instructions
+    computer.set_instruction_limit(Some(100_000));
+
+    for noun in 0..100 {
         for verb in 0..100 {
             computer.memory.replace(1, noun);
             computer.memory.replace(2, verb);
 
-            computer.run();
+            // a noun and verb which make the program fault (or never halt) can't be the answer
+            let halted = computer.run().is_ok();
 
-            if computer.memory.get(0) == DESIRED_OUTPUT {
-                result = Some((noun, verb));
-                break 'outer;
+            if halted && computer.memory.get(0) == DESIRED_OUTPUT {
+                return Ok((100 * noun + verb).into());
             }
 
-            computer.load(&program);
+            computer.load(program);
         }
     }
 
-    match result {
-        None => panic!(
-            "No combination of noun and verb resulted in {}!",
-            DESIRED_OUTPUT
-        ),
-        Some((noun, verb)) => println!(
-            "100 * noun ({}) + verb ({}): {}",
-            noun,
-            verb,
-            100 * noun + verb
-        ),
-    }
+    Err(Error::NoSolution(format!(
+        "no combination of noun and verb resulted in {}",
+        DESIRED_OUTPUT
+    )))
 }
 
 #[cfg(test)]
@@ -136,11 +132,19 @@ mod tests {
         ];
 
         for example in &examples {
-            let mut computer = IntcodeComputer::from(example.0);
+            let mut computer: IntcodeComputer = example.0.parse().unwrap();
 
-            computer.run();
+            computer.run().unwrap();
 
             assert_eq!(computer.memory.data_serialized(), example.1);
         }
     }
+
+    #[test]
+    fn test_invalid_program() {
+        assert_eq!(
+            parse("1,0,0,0,99,x").unwrap_err().to_string(),
+            "invalid input at line 1, column 12: invalid Intcode integer \"x\""
+        );
+    }
 }
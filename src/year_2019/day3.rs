@@ -1,5 +1,21 @@
 //! --- Day 3: Crossed Wires ---
 
+use crate::{Answer, Error, Result};
+use std::str::FromStr;
+
+/// Parses the paths of the two wires in the puzzle input
+pub fn parse(input: &str) -> Result<(Wire, Wire)> {
+    let mut wires = Wire::parse_wires(input)?.into_iter();
+    match (wires.next(), wires.next(), wires.next()) {
+        (Some(first_wire), Some(second_wire), None) => Ok((first_wire, second_wire)),
+        _ => Err(Error::parse(
+            input,
+            &input[input.len()..],
+            "expected two wires",
+        )),
+    }
+}
+
 /// The gravity assist was successful, and you're well on your way to the Venus refuelling station. During the rush back on Earth, the fuel management system wasn't completely installed, so that's next on the priority list.
 ///
 /// Opening the front panel reveals a jumble of wires. Specifically, two wires are connected to a central port and extend outward on a grid. You trace the path each wire takes as it leaves the central port, one wire per line of text (your puzzle input).
@@ -36,31 +52,24 @@
 ///
 /// Here are a few more examples:
 ///
-///     R75,D30,R83,U83,L12,D49,R71,U7,L72
-///     U62,R66,U55,R34,D71,R55,D58,R83 = distance 159
-///     R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51
-///     U98,R91,D20,R16,D67,R40,U7,R15,U6,R7 = distance 135
+/// ```text
+/// R75,D30,R83,U83,L12,D49,R71,U7,L72
+/// U62,R66,U55,R34,D71,R55,D58,R83 = distance 159
+/// R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51
+/// U98,R91,D20,R16,D67,R40,U7,R15,U6,R7 = distance 135
+/// ```
 ///
 /// What is the Manhattan distance from the central port to the closest intersection?
-pub fn part1() {
-    let input = crate::common::read_stdin_to_string();
-    let wires = Wire::parse_wires(input.as_str());
-
-    let first_wire = wires.get(0).expect("Missing first wire");
-    let second_wire = wires.get(1).expect("Missing second wire");
-
+pub fn part1((first_wire, second_wire): &(Wire, Wire)) -> Result<Answer> {
     let origin = Point::zero();
     let min_distance = first_wire
         .intersections(second_wire)
         .iter()
         .map(|intersection| origin.manhattan_distance(intersection))
         .min()
-        .expect("No intersections found!");
+        .ok_or_else(|| Error::NoSolution("the wires never intersect".into()))?;
 
-    println!(
-        "The Manhattan distance from the central port to the closest intersection: {}",
-        min_distance
-    );
+    Ok(min_distance.into())
 }
 
 /// It turns out that this circuit is very timing-sensitive; you actually need to minimize the signal delay.
@@ -86,19 +95,15 @@ pub fn part1() {
 ///
 /// Here are the best steps for the extra examples from above:
 ///
-///     R75,D30,R83,U83,L12,D49,R71,U7,L72
-///     U62,R66,U55,R34,D71,R55,D58,R83 = 610 steps
-///     R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51
-///     U98,R91,D20,R16,D67,R40,U7,R15,U6,R7 = 410 steps
+/// ```text
+/// R75,D30,R83,U83,L12,D49,R71,U7,L72
+/// U62,R66,U55,R34,D71,R55,D58,R83 = 610 steps
+/// R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51
+/// U98,R91,D20,R16,D67,R40,U7,R15,U6,R7 = 410 steps
+/// ```
 ///
 /// What is the fewest combined steps the wires must take to reach an intersection?
-pub fn part2() {
-    let input = crate::common::read_stdin_to_string();
-    let wires = Wire::parse_wires(input.as_str());
-
-    let first_wire = wires.get(0).expect("Missing first wire");
-    let second_wire = wires.get(1).expect("Missing second wire");
-
+pub fn part2((first_wire, second_wire): &(Wire, Wire)) -> Result<Answer> {
     let min_distance = first_wire
         .intersections(second_wire)
         .iter()
@@ -106,16 +111,13 @@ pub fn part2() {
             first_wire.trace_distance(intersection) + second_wire.trace_distance(intersection)
         })
         .min()
-        .expect("No intersections found!");
+        .ok_or_else(|| Error::NoSolution("the wires never intersect".into()))?;
 
-    println!(
-        "The fewest combined steps the wires must take to reach an intersection: {}",
-        min_distance
-    );
+    Ok(min_distance.into())
 }
 
 #[derive(Debug)]
-struct Wire {
+pub struct Wire {
     points: Vec<Point>,
 }
 
@@ -128,8 +130,16 @@ impl Default for Wire {
 }
 
 impl Wire {
-    fn parse_wires(serialized: &str) -> Vec<Self> {
-        serialized.trim().split('\n').map(Wire::from).collect()
+    fn parse_wires(serialized: &str) -> Result<Vec<Self>> {
+        serialized
+            .trim()
+            .split('\n')
+            .enumerate()
+            .map(|(line_index, line)| {
+                line.parse()
+                    .map_err(|error: Error| error.on_line(line_index + 1))
+            })
+            .collect()
     }
 
     fn intersections(&self, target: &Wire) -> Vec<Point> {
@@ -236,18 +246,18 @@ impl Wire {
         distance
     }
 
-    fn add_point_from_segment(&mut self, wire_segment: &str) {
+    fn add_point_from_segment(&mut self, wire_segment: &str) -> Result<(), String> {
         let last_point = self.points.last().cloned().unwrap_or_else(Point::zero);
 
         let mut chars = wire_segment.chars();
-        let direction: WireSegmentDirection = chars
+        let direction = chars
             .next()
-            .unwrap_or_else(|| panic!("Failed to read wire segment {}", wire_segment))
-            .into();
+            .ok_or_else(|| "empty wire segment".to_string())
+            .and_then(WireSegmentDirection::from_char)?;
         let distance: i64 = chars
             .as_str()
             .parse()
-            .expect("Failed to parse distance as u64");
+            .map_err(|_| format!("invalid wire segment distance {:?}", chars.as_str()))?;
 
         match direction {
             WireSegmentDirection::Up => self
@@ -263,19 +273,23 @@ impl Wire {
                 .points
                 .push(Point::new(last_point.x + distance, last_point.y)),
         }
+
+        Ok(())
     }
 }
 
-impl From<&str> for Wire {
-    fn from(wire_serialized: &str) -> Self {
+impl FromStr for Wire {
+    type Err = Error;
+
+    fn from_str(wire_serialized: &str) -> Result<Self, Self::Err> {
         let mut wire: Self = Default::default();
 
-        wire_serialized
-            .trim()
-            .split(',')
-            .for_each(|wire_segment| wire.add_point_from_segment(wire_segment));
+        for wire_segment in wire_serialized.trim().split(',') {
+            wire.add_point_from_segment(wire_segment)
+                .map_err(|message| Error::parse(wire_serialized, wire_segment, message))?;
+        }
 
-        wire
+        Ok(wire)
     }
 }
 
@@ -286,17 +300,17 @@ enum WireSegmentDirection {
     Right,
 }
 
-impl From<char> for WireSegmentDirection {
-    fn from(c: char) -> Self {
+impl WireSegmentDirection {
+    fn from_char(c: char) -> Result<Self, String> {
         match c {
-            'U' => Self::Up,
-            'D' => Self::Down,
-            'L' => Self::Left,
-            'R' => Self::Right,
-            other => panic!(
-                "Invalid wire segment direction (must be U,D,L or R): {}",
+            'U' => Ok(Self::Up),
+            'D' => Ok(Self::Down),
+            'L' => Ok(Self::Left),
+            'R' => Ok(Self::Right),
+            other => Err(format!(
+                "invalid wire segment direction (must be U, D, L or R): {}",
                 other
-            ),
+            )),
         }
     }
 }
@@ -357,9 +371,9 @@ mod tests {
         ];
 
         for example in &examples {
-            let wires = Wire::parse_wires(example.0);
+            let wires = Wire::parse_wires(example.0).unwrap();
 
-            let first_wire = wires.get(0).expect("Missing first wire");
+            let first_wire = wires.first().expect("Missing first wire");
             let second_wire = wires.get(1).expect("Missing second wire");
 
             let origin = Point::zero();
@@ -389,9 +403,9 @@ mod tests {
         ];
 
         for example in &examples {
-            let wires = Wire::parse_wires(example.0);
+            let wires = Wire::parse_wires(example.0).unwrap();
 
-            let first_wire = wires.get(0).expect("Missing first wire");
+            let first_wire = wires.first().expect("Missing first wire");
             let second_wire = wires.get(1).expect("Missing second wire");
 
             let min_distance = first_wire
@@ -407,4 +421,16 @@ mod tests {
             assert_eq!(min_distance, example.1);
         }
     }
+
+    #[test]
+    fn test_invalid_wires() {
+        assert_eq!(
+            parse("R8,U5,L5,D3\nU7,X6,D4,L4").unwrap_err().to_string(),
+            "invalid input at line 2, column 4: invalid wire segment direction (must be U, D, L or R): X"
+        );
+        assert_eq!(
+            parse("R8,U5,L5,D3").unwrap_err().to_string(),
+            "invalid input at line 1, column 12: expected two wires"
+        );
+    }
 }
@@ -1,5 +1,6 @@
 //! --- Day 4: Secure Container ---
 
+use crate::{Answer, Error, Result};
 use itertools::Itertools;
 use std::ops;
 
@@ -7,55 +8,51 @@ use std::ops;
 ///
 /// However, they do remember a few key facts about the password:
 ///
-///     It is a six-digit number.
-///     The value is within the range given in your puzzle input.
-///     Two adjacent digits are the same (like 22 in 122345).
-///     Going from left to right, the digits never decrease; they only ever increase or stay the same (like 111123 or 135679).
+/// ```text
+/// It is a six-digit number.
+/// The value is within the range given in your puzzle input.
+/// Two adjacent digits are the same (like 22 in 122345).
+/// Going from left to right, the digits never decrease; they only ever increase or stay the same (like 111123 or 135679).
+/// ```
 ///
 /// Other than the range rule, the following are true:
 ///
-///     111111 meets these criteria (double 11, never decreases).
-///     223450 does not meet these criteria (decreasing pair of digits 50).
-///     123789 does not meet these criteria (no double).
+/// ```text
+/// 111111 meets these criteria (double 11, never decreases).
+/// 223450 does not meet these criteria (decreasing pair of digits 50).
+/// 123789 does not meet these criteria (no double).
+/// ```
 ///
 /// How many different passwords within the range given in your puzzle input meet these criteria?
-pub fn part1() {
-    let input = crate::common::read_stdin_to_string();
-    let range = ops::RangeInclusive::<i64>::from_str(input.as_str());
-
+pub fn part1(range: &ops::RangeInclusive<i64>) -> Result<Answer> {
     let number_of_passwords = range
+        .clone()
         .map(|integer| integer.to_string())
         .filter(|password| part1_test_password_against_facts(password.as_str()))
         .count();
 
-    println!(
-        "The number of different passwords within the range given which meet these criteria: {}",
-        number_of_passwords
-    );
+    Ok(number_of_passwords.into())
 }
 
 /// An Elf just remembered one more important detail: the two adjacent matching digits are not part of a larger group of matching digits.
 ///
 /// Given this additional criterion, but still ignoring the range rule, the following are now true:
 ///
-///     112233 meets these criteria because the digits never decrease and all repeated digits are exactly two digits long.
-///     123444 no longer meets the criteria (the repeated 44 is part of a larger group of 444).
-///     111122 meets the criteria (even though 1 is repeated more than twice, it still contains a double 22).
+/// ```text
+/// 112233 meets these criteria because the digits never decrease and all repeated digits are exactly two digits long.
+/// 123444 no longer meets the criteria (the repeated 44 is part of a larger group of 444).
+/// 111122 meets the criteria (even though 1 is repeated more than twice, it still contains a double 22).
+/// ```
 ///
 /// How many different passwords within the range given in your puzzle input meet all of the criteria?
-pub fn part2() {
-    let input = crate::common::read_stdin_to_string();
-    let range = ops::RangeInclusive::<i64>::from_str(input.as_str());
-
+pub fn part2(range: &ops::RangeInclusive<i64>) -> Result<Answer> {
     let number_of_passwords = range
+        .clone()
         .map(|integer| integer.to_string())
         .filter(|password| part2_test_password_against_facts(password.as_str()))
         .count();
 
-    println!(
-        "The number of different passwords within the range given which meet all of the criteria: {}",
-        number_of_passwords
-    );
+    Ok(number_of_passwords.into())
 }
 
 fn part1_test_password_against_facts(password: &str) -> bool {
@@ -113,23 +110,19 @@ fn part2_test_password_against_facts(password: &str) -> bool {
     true
 }
 
-trait FromStr {
-    fn from_str(string: &str) -> Self;
-}
-
-impl FromStr for ops::RangeInclusive<i64> {
-    fn from_str(string: &str) -> Self {
-        let mut iter = string
-            .trim()
-            .split('-')
-            .map(|integer| integer.parse())
-            .map(|parse_result| parse_result.expect("Failed to parse range integer as i64"));
-
-        let start = iter.next().expect("Failed to parse range start");
-        let end = iter.next().expect("Failed to parse range end");
-
-        start..=end
-    }
+/// Parses the range of passwords in the puzzle input
+pub fn parse(input: &str) -> Result<ops::RangeInclusive<i64>> {
+    let range = input.trim();
+    let (start, end) = range
+        .split_once('-')
+        .ok_or_else(|| Error::parse(input, range, "expected a range like '123456-654321'"))?;
+    let integer = |integer: &str| {
+        integer
+            .parse()
+            .map_err(|_| Error::parse(input, integer, format!("invalid range bound {:?}", integer)))
+    };
+
+    Ok(integer(start)?..=integer(end)?)
 }
 
 #[cfg(test)]
@@ -153,4 +146,16 @@ mod tests {
             assert_eq!(part2_test_password_against_facts(example.0), example.1);
         }
     }
+
+    #[test]
+    fn test_invalid_range() {
+        assert_eq!(
+            parse("123456").unwrap_err().to_string(),
+            "invalid input at line 1, column 1: expected a range like '123456-654321'"
+        );
+        assert_eq!(
+            parse("123456-65432l").unwrap_err().to_string(),
+            "invalid input at line 1, column 8: invalid range bound \"65432l\""
+        );
+    }
 }
@@ -1,6 +1,12 @@
 //! --- Day 5: Sunny with a Chance of Asteroids ---
 
-use super::IntcodeComputer;
+use super::{IntcodeComputer, IntcodeProgram};
+use crate::{Answer, Error, Result};
+
+/// Parses the Intcode program in the puzzle input
+pub fn parse(input: &str) -> Result<IntcodeProgram> {
+    input.parse()
+}
 
 /// You're starting to sweat as the ship makes its way toward Mercury. The Elves suggest that you get the air conditioner working by upgrading your ship computer to support the Thermal Environment Supervision Terminal.
 ///
@@ -8,8 +14,10 @@ use super::IntcodeComputer;
 ///
 /// First, you'll need to add two new instructions:
 ///
-///     Opcode 3 takes a single integer as input and saves it to the address given by its only parameter. For example, the instruction 3,50 would take an input value and store it at address 50.
-///     Opcode 4 outputs the value of its only parameter. For example, the instruction 4,50 would output the value at address 50.
+/// ```text
+/// Opcode 3 takes a single integer as input and saves it to the address given by its only parameter. For example, the instruction 3,50 would take an input value and store it at address 50.
+/// Opcode 4 outputs the value of its only parameter. For example, the instruction 4,50 would output the value at address 50.
+/// ```
 ///
 /// Programs that use these instructions will come with documentation that explains what should be connected to the input and output. The program 3,0,4,0,99 outputs whatever it gets as input, then halts.
 ///
@@ -40,8 +48,10 @@ use super::IntcodeComputer;
 ///
 /// Finally, some notes:
 ///
-///     It is important to remember that the instruction pointer should increase by the number of values in the instruction after the instruction finishes. Because of the new instructions, this amount is no longer always 4.
-///     Integers can be negative: 1101,100,-1,4,0 is a valid program (find 100 + -1, store the result in position 4).
+/// ```text
+/// It is important to remember that the instruction pointer should increase by the number of values in the instruction after the instruction finishes. Because of the new instructions, this amount is no longer always 4.
+/// Integers can be negative: 1101,100,-1,4,0 is a valid program (find 100 + -1, store the result in position 4).
+/// ```
 ///
 /// The TEST diagnostic program will start by requesting from the user the ID of the system to test by running an input instruction - provide it 1, the ID for the ship's air conditioner unit.
 ///
@@ -50,31 +60,25 @@ use super::IntcodeComputer;
 /// Finally, the program will output a diagnostic code and immediately halt. This final output isn't an error; an output followed immediately by a halt means the program finished. If all outputs were zero except the diagnostic code, the diagnostic program ran successfully.
 ///
 /// After providing 1 to the only input instruction and passing all the tests, what diagnostic code does the program produce?
-pub fn part1() {
-    let input = crate::common::read_stdin_to_string();
-    let mut computer = IntcodeComputer::from(input.as_str());
-
-    let input_tx = computer.create_input();
-    let output_rx = computer.create_output();
+pub fn part1(program: &IntcodeProgram) -> Result<Answer> {
+    let mut computer = IntcodeComputer::from(program);
 
     const TEST_SYSTEM_ID: i64 = 1;
 
-    input_tx.send(TEST_SYSTEM_ID).unwrap();
-    computer.run();
-
-    let mut diagnostic_code = 0;
-    for (index, output) in output_rx.iter().enumerate() {
-        if output == 0 {
-            println!("Test {} passed!", index + 1);
-        }
-
-        diagnostic_code = output;
+    let outputs = computer.run_with_input(vec![TEST_SYSTEM_ID])?;
+    let (diagnostic_code, test_results) = outputs
+        .split_last()
+        .ok_or_else(|| Error::NoSolution("the diagnostic program produced no output".into()))?;
+
+    if let Some(failed_test) = test_results.iter().position(|output| *output != 0) {
+        return Err(Error::NoSolution(format!(
+            "diagnostic test {} failed with output {}",
+            failed_test + 1,
+            test_results[failed_test]
+        )));
     }
 
-    println!(
-        "After providing 1 to the only input instruction and passing all the tests, the diagnostic code produced: {}",
-        diagnostic_code
-    );
+    Ok((*diagnostic_code).into())
 }
 
 /// The air conditioner comes online! Its cold air feels good for a while, but then the TEST alarms start to go off. Since the air conditioner can't vent its heat anywhere but back into the spacecraft, it's actually making the air inside the ship warmer.
@@ -83,10 +87,12 @@ pub fn part1() {
 ///
 /// Your computer is only missing a few opcodes:
 ///
-///     Opcode 5 is jump-if-true: if the first parameter is non-zero, it sets the instruction pointer to the value from the second parameter. Otherwise, it does nothing.
-///     Opcode 6 is jump-if-false: if the first parameter is zero, it sets the instruction pointer to the value from the second parameter. Otherwise, it does nothing.
-///     Opcode 7 is less than: if the first parameter is less than the second parameter, it stores 1 in the position given by the third parameter. Otherwise, it stores 0.
-///     Opcode 8 is equals: if the first parameter is equal to the second parameter, it stores 1 in the position given by the third parameter. Otherwise, it stores 0.
+/// ```text
+/// Opcode 5 is jump-if-true: if the first parameter is non-zero, it sets the instruction pointer to the value from the second parameter. Otherwise, it does nothing.
+/// Opcode 6 is jump-if-false: if the first parameter is zero, it sets the instruction pointer to the value from the second parameter. Otherwise, it does nothing.
+/// Opcode 7 is less than: if the first parameter is less than the second parameter, it stores 1 in the position given by the third parameter. Otherwise, it stores 0.
+/// Opcode 8 is equals: if the first parameter is equal to the second parameter, it stores 1 in the position given by the third parameter. Otherwise, it stores 0.
+/// ```
 ///
 /// Like all instructions, these instructions need to support parameter modes as described above.
 ///
@@ -94,15 +100,19 @@ pub fn part1() {
 ///
 /// For example, here are several programs that take one input, compare it to the value 8, and then produce one output:
 ///
-///     3,9,8,9,10,9,4,9,99,-1,8 - Using position mode, consider whether the input is equal to 8; output 1 (if it is) or 0 (if it is not).
-///     3,9,7,9,10,9,4,9,99,-1,8 - Using position mode, consider whether the input is less than 8; output 1 (if it is) or 0 (if it is not).
-///     3,3,1108,-1,8,3,4,3,99 - Using immediate mode, consider whether the input is equal to 8; output 1 (if it is) or 0 (if it is not).
-///     3,3,1107,-1,8,3,4,3,99 - Using immediate mode, consider whether the input is less than 8; output 1 (if it is) or 0 (if it is not).
+/// ```text
+/// 3,9,8,9,10,9,4,9,99,-1,8 - Using position mode, consider whether the input is equal to 8; output 1 (if it is) or 0 (if it is not).
+/// 3,9,7,9,10,9,4,9,99,-1,8 - Using position mode, consider whether the input is less than 8; output 1 (if it is) or 0 (if it is not).
+/// 3,3,1108,-1,8,3,4,3,99 - Using immediate mode, consider whether the input is equal to 8; output 1 (if it is) or 0 (if it is not).
+/// 3,3,1107,-1,8,3,4,3,99 - Using immediate mode, consider whether the input is less than 8; output 1 (if it is) or 0 (if it is not).
+/// ```
 ///
 /// Here are some jump tests that take an input, then output 0 if the input was zero or 1 if the input was non-zero:
 ///
-///     3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9 (using position mode)
-///     3,3,1105,-1,9,1101,0,0,12,4,12,99,1 (using immediate mode)
+/// ```text
+/// 3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9 (using position mode)
+/// 3,3,1105,-1,9,1101,0,0,12,4,12,99,1 (using immediate mode)
+/// ```
 ///
 /// Here's a larger example:
 ///
@@ -115,21 +125,17 @@ pub fn part1() {
 /// This time, when the TEST diagnostic program runs its input instruction to get the ID of the system to test, provide it 5, the ID for the ship's thermal radiator controller. This diagnostic test suite only outputs one number, the diagnostic code.
 ///
 /// What is the diagnostic code for system ID 5?
-pub fn part2() {
-    let input = crate::common::read_stdin_to_string();
-    let mut computer = IntcodeComputer::from(input.as_str());
-
-    let input_tx = computer.create_input();
-    let output_rx = computer.create_output();
+pub fn part2(program: &IntcodeProgram) -> Result<Answer> {
+    let mut computer = IntcodeComputer::from(program);
 
     const TEST_SYSTEM_ID: i64 = 5;
 
-    input_tx.send(TEST_SYSTEM_ID).unwrap();
-    computer.run();
-
-    let diagnostic_code = output_rx.recv().unwrap();
+    let outputs = computer.run_with_input(vec![TEST_SYSTEM_ID])?;
+    let diagnostic_code = outputs
+        .first()
+        .ok_or_else(|| Error::NoSolution("the diagnostic program produced no output".into()))?;
 
-    println!("The diagnostic code for system ID 5: {}", diagnostic_code);
+    Ok((*diagnostic_code).into())
 }
 
 #[cfg(test)]
@@ -138,13 +144,13 @@ mod tests {
 
     #[test]
     fn test_intcode_computer_input_output() {
-        let mut computer = IntcodeComputer::from("3,0,4,0,99");
+        let mut computer: IntcodeComputer = "3,0,4,0,99".parse().unwrap();
 
         let input_tx = computer.create_input();
         let output_rx = computer.create_output();
 
         input_tx.send(42).unwrap();
-        computer.run();
+        computer.run().unwrap();
 
         assert_eq!(output_rx.recv().unwrap(), 42);
     }
@@ -180,13 +186,13 @@ mod tests {
         ];
 
         for example in &examples {
-            let mut computer = IntcodeComputer::from(example.0);
+            let mut computer: IntcodeComputer = example.0.parse().unwrap();
 
             let input_tx = computer.create_input();
             let output_rx = computer.create_output();
 
             input_tx.send(example.1).unwrap();
-            computer.run();
+            computer.run().unwrap();
 
             assert_eq!(output_rx.recv().unwrap(), example.2);
         }
@@ -1,11 +1,19 @@
 //! --- Day 6: Universal Orbit Map ---
 
+use crate::{Answer, Error, Result};
 use std::collections::HashMap;
+use std::convert::TryFrom;
+
+/// Parses the map of local orbits in the puzzle input
+pub fn parse(input: &str) -> Result<OrbitMap<'_>> {
+    OrbitMap::try_from(input)
+}
 
 /// You've landed at the Universal Orbit Map facility on Mercury. Because navigation in space often involves transferring between orbits, the orbit maps here are useful for finding efficient routes between, for example, you and Santa. You download a map of the local orbits (your puzzle input).
 ///
 /// Except for the universal Center of Mass (COM), every object in space is in orbit around exactly one other object. An orbit looks roughly like this:
 ///
+/// ```text
 ///                   \
 ///                    \
 ///                     |
@@ -15,6 +23,7 @@ use std::collections::HashMap;
 ///                     |
 ///                    /
 ///                   /
+/// ```
 ///
 /// In this diagram, the object BBB is in orbit around AAA. The path that BBB takes around AAA (drawn with lines) is only partly shown. In the map data, this orbital relationship is written AAA)BBB, which means "BBB is in orbit around AAA".
 ///
@@ -38,32 +47,31 @@ use std::collections::HashMap;
 ///
 /// Visually, the above map of orbits looks like this:
 ///
+/// ```text
 ///         G - H       J - K - L
 ///        /           /
 /// COM - B - C - D - E - F
 ///                \
 ///                 I
+/// ```
 ///
 /// In this visual representation, when two objects are connected by a line, the one on the right directly orbits the one on the left.
 ///
 /// Here, we can count the total number of orbits as follows:
 ///
-///     D directly orbits C and indirectly orbits B and COM, a total of 3 orbits.
-///     L directly orbits K and indirectly orbits J, E, D, C, B, and COM, a total of 7 orbits.
-///     COM orbits nothing.
+/// ```text
+/// D directly orbits C and indirectly orbits B and COM, a total of 3 orbits.
+/// L directly orbits K and indirectly orbits J, E, D, C, B, and COM, a total of 7 orbits.
+/// COM orbits nothing.
+/// ```
 ///
 /// The total number of direct and indirect orbits in this example is 42.
 ///
 /// What is the total number of direct and indirect orbits in your map data?
-pub fn part1() {
-    let input = crate::common::read_stdin_to_string();
-    let orbit_map = OrbitMap::from(input.as_str());
+pub fn part1(orbit_map: &OrbitMap<'_>) -> Result<Answer> {
     let total_orbits = orbit_map.orbit_count_checksum();
 
-    println!(
-        "The total number of direct and indirect orbits in your map data: {}",
-        total_orbits
-    );
+    Ok(total_orbits.into())
 }
 
 /// Now, you just need to figure out how many orbital transfers you (YOU) need to take to get to Santa (SAN).
@@ -88,6 +96,7 @@ pub fn part1() {
 ///
 /// Visually, the above map of orbits looks like this:
 ///
+/// ```text
 ///                           YOU
 ///                          /
 ///         G - H       J - K - L
@@ -95,16 +104,20 @@ pub fn part1() {
 /// COM - B - C - D - E - F
 ///                \
 ///                 I - SAN
+/// ```
 ///
 /// In this example, YOU are in orbit around K, and SAN is in orbit around I. To move from K to I, a minimum of 4 orbital transfers are required:
 ///
-///     K to J
-///     J to E
-///     E to D
-///     D to I
+/// ```text
+/// K to J
+/// J to E
+/// E to D
+/// D to I
+/// ```
 ///
 /// Afterward, the map of orbits looks like this:
 ///
+/// ```text
 ///         G - H       J - K - L
 ///        /           /
 /// COM - B - C - D - E - F
@@ -112,41 +125,37 @@ pub fn part1() {
 ///                 I - SAN
 ///                  \
 ///                   YOU
+/// ```
 ///
 /// What is the minimum number of orbital transfers required to move from the object YOU are orbiting to the object SAN is orbiting? (Between the objects they are orbiting - not between YOU and SAN.libunwind
-pub fn part2() {
-    let input = crate::common::read_stdin_to_string();
-    let orbit_map = OrbitMap::from(input.as_str());
-    let minimum_transfers = orbit_map.minimum_transfers("SAN", "YOU");
-
-    println!(
-        "The minimum number of orbital transfers required to move from the object YOU are orbiting to the object SAN is orbiting: {}",
-        minimum_transfers
-    );
+pub fn part2(orbit_map: &OrbitMap<'_>) -> Result<Answer> {
+    let minimum_transfers = orbit_map.minimum_transfers("SAN", "YOU")?;
+
+    Ok(minimum_transfers.into())
 }
 
 #[derive(Debug, Default)]
-struct OrbitMap<'a> {
+pub struct OrbitMap<'a> {
     bodies: HashMap<&'a str, OrbitMapBody<'a>>,
 }
 
 impl<'a> OrbitMap<'a> {
-    fn get_body(&self, id: &str) -> &OrbitMapBody {
+    fn get_body(&self, id: &str) -> Result<&OrbitMapBody<'_>> {
         self.bodies
             .get(id)
-            .unwrap_or_else(|| panic!("{} body not found in OrbitMap", id))
+            .ok_or_else(|| Error::NoSolution(format!("{} body not found in OrbitMap", id)))
     }
 
-    fn get_body_parent(&self, id: &str) -> &OrbitMapBody {
+    fn get_body_parent(&self, id: &str) -> Result<&OrbitMapBody<'_>> {
         let parent_id = self
-            .get_body(id)
+            .get_body(id)?
             .parent
-            .unwrap_or_else(|| panic!("{} body missing parent", id));
+            .ok_or_else(|| Error::NoSolution(format!("{} body missing parent", id)))?;
 
         self.get_body(parent_id)
     }
 
-    fn add_orbit_relation(&mut self, target_id: &'a str, source_id: &'a str) {
+    fn add_orbit_relation(&mut self, target_id: &'a str, source_id: &'a str) -> Result<(), String> {
         // insert target if it doesn't exist
         self.bodies
             .entry(target_id)
@@ -160,12 +169,11 @@ impl<'a> OrbitMap<'a> {
 
         // set source.parent to target
         if let Some(parent_id) = &source.parent {
-            panic!(
-                "Failed adding orbit relation, source body {} already has a parent: {}",
-                source_id, parent_id
-            );
+            return Err(format!("{} already orbits {}", source_id, parent_id));
         }
         source.parent = Some(target_id);
+
+        Ok(())
     }
 
     fn orbit_count_checksum(&self) -> usize {
@@ -175,9 +183,9 @@ impl<'a> OrbitMap<'a> {
             .sum()
     }
 
-    fn minimum_transfers(&self, target_id: &'a str, source_id: &'a str) -> usize {
-        let source = self.get_body_parent(source_id);
-        let target = self.get_body_parent(target_id);
+    fn minimum_transfers(&self, target_id: &'a str, source_id: &'a str) -> Result<usize> {
+        let source = self.get_body_parent(source_id)?;
+        let target = self.get_body_parent(target_id)?;
 
         let source_parents: Vec<_> = source.parents(self).collect();
         let target_parents: Vec<_> = target.parents(self).collect();
@@ -197,12 +205,12 @@ impl<'a> OrbitMap<'a> {
             }
         }
 
-        let common_parent_id = common_parent.unwrap_or_else(|| {
-            panic!(
-                "Failed to find common parent of {} and {}",
+        let common_parent_id = common_parent.ok_or_else(|| {
+            Error::NoSolution(format!(
+                "{} and {} have no common parent",
                 source_id, target_id
-            )
-        });
+            ))
+        })?;
 
         for parent in target_parents.iter() {
             minimum_transfers += 1;
@@ -212,28 +220,27 @@ impl<'a> OrbitMap<'a> {
             }
         }
 
-        minimum_transfers
+        Ok(minimum_transfers)
     }
 }
 
-impl<'a> From<&'a str> for OrbitMap<'a> {
-    fn from(string: &'a str) -> Self {
-        string
-            .trim()
-            .split('\n')
-            .map(|orbit| (orbit, orbit.split(')')))
-            .fold(Default::default(), |mut orbit_map, (orbit, mut bodies)| {
-                orbit_map.add_orbit_relation(
-                    bodies
-                        .next()
-                        .unwrap_or_else(|| panic!("Failed to parse orbit relation {}", orbit)),
-                    bodies
-                        .next()
-                        .unwrap_or_else(|| panic!("Failed to parse orbit relation {}", orbit)),
-                );
-
-                orbit_map
-            })
+impl<'a> TryFrom<&'a str> for OrbitMap<'a> {
+    type Error = Error;
+
+    fn try_from(string: &'a str) -> Result<Self> {
+        let mut orbit_map: Self = Default::default();
+
+        for orbit in string.trim().split('\n') {
+            let (target_id, source_id) = orbit
+                .split_once(')')
+                .ok_or_else(|| Error::parse(string, orbit, "expected an orbit like 'A)B'"))?;
+
+            orbit_map
+                .add_orbit_relation(target_id, source_id)
+                .map_err(|message| Error::parse(string, orbit, message))?;
+        }
+
+        Ok(orbit_map)
     }
 }
 
@@ -265,13 +272,7 @@ impl<'a> Iterator for OrbitMapBodyParentIterator<'a> {
     type Item = &'a OrbitMapBody<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let next_parent = match self.next_parent_id {
-            Some(next_parent_id) => match self.map.bodies.get(next_parent_id) {
-                Some(next_parent) => next_parent,
-                None => return None,
-            },
-            None => return None,
-        };
+        let next_parent = self.map.bodies.get(self.next_parent_id?)?;
 
         self.next_parent_id = next_parent.parent;
 
@@ -290,7 +291,12 @@ mod tests {
             42,
         );
 
-        assert_eq!(OrbitMap::from(example.0).orbit_count_checksum(), example.1);
+        assert_eq!(
+            OrbitMap::try_from(example.0)
+                .unwrap()
+                .orbit_count_checksum(),
+            example.1
+        );
     }
 
     #[test]
@@ -301,8 +307,23 @@ mod tests {
         );
 
         assert_eq!(
-            OrbitMap::from(example.0).minimum_transfers("SAN", "YOU"),
+            OrbitMap::try_from(example.0)
+                .unwrap()
+                .minimum_transfers("SAN", "YOU")
+                .unwrap(),
             example.1
         );
     }
+
+    #[test]
+    fn test_invalid_orbit_map() {
+        assert_eq!(
+            OrbitMap::try_from("COM)B\nB-C").unwrap_err().to_string(),
+            "invalid input at line 2, column 1: expected an orbit like 'A)B'"
+        );
+        assert_eq!(
+            OrbitMap::try_from("COM)B\nA)B").unwrap_err().to_string(),
+            "invalid input at line 2, column 1: B already orbits COM"
+        );
+    }
 }
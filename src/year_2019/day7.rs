@@ -1,16 +1,23 @@
 //! --- Day 7: Amplification Circuit ---
 
-use super::{IntcodeComputer, IntcodeProgram};
-use itertools::Itertools;
-use std::sync::mpsc::{Receiver, SendError, Sender};
+use super::intcode_pipeline::max_over_permutations;
+use super::{IntcodePipeline, IntcodeProgram};
+use crate::{Answer, Error, Result};
+
+/// Parses the amplifier controller software in the puzzle input
+pub fn parse(input: &str) -> Result<IntcodeProgram> {
+    input.parse()
+}
 
 /// Based on the navigational maps, you're going to need to send more power to your ship's thrusters to reach Santa in time. To do this, you'll need to configure a series of amplifiers already installed on the ship.
 ///
 /// There are five amplifiers connected in series; each one receives an input signal and produces an output signal. They are connected such that the first amplifier's output leads to the second amplifier's input, the second amplifier's output leads to the third amplifier's input, and so on. The first amplifier's input value is 0, and the last amplifier's output leads to your ship's thrusters.
 ///
+/// ```text
 ///     O-------O  O-------O  O-------O  O-------O  O-------O
 /// 0 ->| Amp A |->| Amp B |->| Amp C |->| Amp D |->| Amp E |-> (to thrusters)
 ///     O-------O  O-------O  O-------O  O-------O  O-------O
+/// ```
 ///
 /// The Elves have sent you some Amplifier Controller Software (your puzzle input), a program that should run on your existing Intcode computer. Each amplifier will need to run a copy of the program.
 ///
@@ -22,44 +29,44 @@ use std::sync::mpsc::{Receiver, SendError, Sender};
 ///
 /// For example, suppose you want to try the phase setting sequence 3,1,2,4,0, which would mean setting amplifier A to phase setting 3, amplifier B to setting 1, C to 2, D to 4, and E to 0. Then, you could determine the output signal that gets sent from amplifier E to the thrusters with the following steps:
 ///
-///     Start the copy of the amplifier controller software that will run on amplifier A. At its first input instruction, provide it the amplifier's phase setting, 3. At its second input instruction, provide it the input signal, 0. After some calculations, it will use an output instruction to indicate the amplifier's output signal.
-///     Start the software for amplifier B. Provide it the phase setting (1) and then whatever output signal was produced from amplifier A. It will then produce a new output signal destined for amplifier C.
-///     Start the software for amplifier C, provide the phase setting (2) and the value from amplifier B, then collect its output signal.
-///     Run amplifier D's software, provide the phase setting (4) and input value, and collect its output signal.
-///     Run amplifier E's software, provide the phase setting (0) and input value, and collect its output signal.
+/// ```text
+/// Start the copy of the amplifier controller software that will run on amplifier A. At its first input instruction, provide it the amplifier's phase setting, 3. At its second input instruction, provide it the input signal, 0. After some calculations, it will use an output instruction to indicate the amplifier's output signal.
+/// Start the software for amplifier B. Provide it the phase setting (1) and then whatever output signal was produced from amplifier A. It will then produce a new output signal destined for amplifier C.
+/// Start the software for amplifier C, provide the phase setting (2) and the value from amplifier B, then collect its output signal.
+/// Run amplifier D's software, provide the phase setting (4) and input value, and collect its output signal.
+/// Run amplifier E's software, provide the phase setting (0) and input value, and collect its output signal.
+/// ```
 ///
 /// The final output signal from amplifier E would be sent to the thrusters. However, this phase setting sequence may not have been the best one; another sequence might have sent a higher signal to the thrusters.
 ///
 /// Here are some example programs:
 ///
-///     Max thruster signal 43210 (from phase setting sequence 4,3,2,1,0):
+/// ```text
+/// Max thruster signal 43210 (from phase setting sequence 4,3,2,1,0):
 ///
-///     3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0
+/// 3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0
 ///
-///     Max thruster signal 54321 (from phase setting sequence 0,1,2,3,4):
+/// Max thruster signal 54321 (from phase setting sequence 0,1,2,3,4):
 ///
-///     3,23,3,24,1002,24,10,24,1002,23,-1,23,
-///     101,5,23,23,1,24,23,23,4,23,99,0,0
+/// 3,23,3,24,1002,24,10,24,1002,23,-1,23,
+/// 101,5,23,23,1,24,23,23,4,23,99,0,0
 ///
-///     Max thruster signal 65210 (from phase setting sequence 1,0,4,3,2):
+/// Max thruster signal 65210 (from phase setting sequence 1,0,4,3,2):
 ///
-///     3,31,3,32,1002,32,10,32,1001,31,-2,31,1007,31,0,33,
-///     1002,33,7,33,1,33,31,31,1,32,31,31,4,31,99,0,0,0
+/// 3,31,3,32,1002,32,10,32,1001,31,-2,31,1007,31,0,33,
+/// 1002,33,7,33,1,33,31,31,1,32,31,31,4,31,99,0,0,0
+/// ```
 ///
 /// Try every combination of phase settings on the amplifiers. What is the highest signal that can be sent to the thrusters?
-pub fn part1() {
-    let input = crate::common::read_stdin_to_string();
-    let amplifier_controller = IntcodeProgram::from(input.as_str());
-    let highest_signal = part1_calculate_highest_signal(&amplifier_controller);
-
-    println!(
-        "The highest signal that can be sent to the thrusters: {}",
-        highest_signal
-    );
+pub fn part1(amplifier_controller: &IntcodeProgram) -> Result<Answer> {
+    let highest_signal = part1_calculate_highest_signal(amplifier_controller)?;
+
+    Ok(highest_signal.into())
 }
 
 /// It's no good - in this configuration, the amplifiers can't generate a large enough output signal to produce the thrust you'll need. The Elves quickly talk you through rewiring the amplifiers into a feedback loop:
 ///
+/// ```text
 ///       O-------O  O-------O  O-------O  O-------O  O-------O
 /// 0 -+->| Amp A |->| Amp B |->| Amp C |->| Amp D |->| Amp E |-.
 ///    |  O-------O  O-------O  O-------O  O-------O  O-------O |
@@ -68,6 +75,7 @@ pub fn part1() {
 ///                                                             |
 ///                                                             v
 ///                                                      (to thrusters)
+/// ```
 ///
 /// Most of the amplifiers are connected as they were before; amplifier A's output is connected to amplifier B's input, and so on. However, the output from amplifier E is now connected into amplifier A's input. This creates the feedback loop: the signal will be sent through the amplifiers many times.
 ///
@@ -81,105 +89,57 @@ pub fn part1() {
 ///
 /// Here are some example programs:
 ///
-///     Max thruster signal 139629729 (from phase setting sequence 9,8,7,6,5):
+/// ```text
+/// Max thruster signal 139629729 (from phase setting sequence 9,8,7,6,5):
 ///
-///     3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,
-///     27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5
+/// 3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,
+/// 27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5
 ///
-///     Max thruster signal 18216 (from phase setting sequence 9,7,8,5,6):
+/// Max thruster signal 18216 (from phase setting sequence 9,7,8,5,6):
 ///
-///     3,52,1001,52,-5,52,3,53,1,52,56,54,1007,54,5,55,1005,55,26,1001,54,
-///     -5,54,1105,1,12,1,53,54,53,1008,54,0,55,1001,55,1,55,2,53,55,53,4,
-///     53,1001,56,-1,56,1005,56,6,99,0,0,0,0,10
+/// 3,52,1001,52,-5,52,3,53,1,52,56,54,1007,54,5,55,1005,55,26,1001,54,
+/// -5,54,1105,1,12,1,53,54,53,1008,54,0,55,1001,55,1,55,2,53,55,53,4,
+/// 53,1001,56,-1,56,1005,56,6,99,0,0,0,0,10
+/// ```
 ///
 /// Try every combination of the new phase settings on the amplifier feedback loop. What is the highest signal that can be sent to the thrusters?
-pub fn part2() {
-    let input = crate::common::read_stdin_to_string();
-    let amplifier_controller = IntcodeProgram::from(input.as_str());
-    let highest_signal = part2_calculate_highest_signal(&amplifier_controller);
-
-    println!(
-        "The highest signal that can be sent to the thrusters: {}",
-        highest_signal
-    );
-}
+pub fn part2(amplifier_controller: &IntcodeProgram) -> Result<Answer> {
+    let highest_signal = part2_calculate_highest_signal(amplifier_controller)?;
 
-fn part1_calculate_highest_signal(amplifier_controller: &IntcodeProgram) -> i64 {
-    const PHASE_SETTINGS_START: i64 = 0;
-    const PHASE_SETTINGS_COUNT: usize = 5;
-
-    (PHASE_SETTINGS_START..PHASE_SETTINGS_START + (PHASE_SETTINGS_COUNT as i64))
-        .permutations(PHASE_SETTINGS_COUNT)
-        .map(|phase_settings| {
-            phase_settings
-                .iter()
-                .map(|phase_setting| Amplifier::new(amplifier_controller, *phase_setting))
-                .fold(0, |signal, amplifier| {
-                    amplifier
-                        .amplify_signal(signal)
-                        .expect("Failed to amplify signal")
-                })
-        })
-        .max()
-        .expect("Failed to generate any phase settings")
+    Ok(highest_signal.into())
 }
 
-fn part2_calculate_highest_signal(amplifier_controller: &IntcodeProgram) -> i64 {
-    const PHASE_SETTINGS_START: i64 = 5;
-    const PHASE_SETTINGS_COUNT: usize = 5;
-
-    (PHASE_SETTINGS_START..PHASE_SETTINGS_START + (PHASE_SETTINGS_COUNT as i64))
-        .permutations(PHASE_SETTINGS_COUNT)
-        .map(|phase_settings| {
-            phase_settings
-                .iter()
-                .map(|phase_setting| Amplifier::new(amplifier_controller, *phase_setting))
-                .collect()
-        })
-        .map(|amplifiers: Vec<_>| {
-            let mut signal = 0;
-
-            for amplifier in amplifiers.iter().cycle() {
-                signal = match amplifier.amplify_signal(signal) {
-                    Some(signal) => signal,
-                    None => break,
-                }
-            }
-
-            signal
-        })
-        .max()
-        .expect("Failed to generate any phase settings")
+fn part1_calculate_highest_signal(amplifier_controller: &IntcodeProgram) -> Result<i64> {
+    calculate_highest_signal(amplifier_controller, &[0, 1, 2, 3, 4], false)
 }
 
-struct Amplifier {
-    input: Sender<i64>,
-    output: Receiver<i64>,
+fn part2_calculate_highest_signal(amplifier_controller: &IntcodeProgram) -> Result<i64> {
+    calculate_highest_signal(amplifier_controller, &[5, 6, 7, 8, 9], true)
 }
 
-impl Amplifier {
-    fn new(controller_rom: &IntcodeProgram, phase_setting: i64) -> Self {
-        let (input, output) = IntcodeComputer::run_new_in_thread(controller_rom.clone());
-
-        input
-            .send(phase_setting)
-            .expect("Failed to send phase_setting to amplifier controller");
-
-        Self { input, output }
-    }
-
-    fn amplify_signal(&self, signal: i64) -> Option<i64> {
-        let result = self.input.send(signal);
-        if let Err(SendError(_)) = result {
-            return None;
+/// Tries every ordering of `phase_settings` on a chain of amplifiers, one for each phase setting
+fn calculate_highest_signal(
+    amplifier_controller: &IntcodeProgram,
+    phase_settings: &[i64],
+    feedback_loop: bool,
+) -> Result<i64> {
+    let highest = max_over_permutations(phase_settings, |phase_settings| {
+        let mut amplifiers = IntcodePipeline::new(amplifier_controller, phase_settings.len());
+        for (amplifier, phase_setting) in phase_settings.iter().enumerate() {
+            amplifiers.push_input(amplifier, *phase_setting);
+        }
+        if feedback_loop {
+            amplifiers.add_feedback(phase_settings.len() - 1, 0);
         }
 
-        let signal = self
-            .output
-            .recv()
-            .expect("Failed to receive amplifier controller output");
+        Ok(amplifiers.run(&[0])?.last().copied())
+    })?;
 
-        Some(signal)
+    match highest {
+        Some((signal, _)) => Ok(signal),
+        None => Err(Error::NoSolution(
+            "the amplifiers never sent a signal to the thrusters".into(),
+        )),
     }
 }
 
@@ -196,9 +156,9 @@ mod tests {
         ];
 
         for example in &examples {
-            let amplifier_controller = IntcodeProgram::from(example.0);
+            let amplifier_controller: IntcodeProgram = example.0.parse().unwrap();
             assert_eq!(
-                part1_calculate_highest_signal(&amplifier_controller),
+                part1_calculate_highest_signal(&amplifier_controller).unwrap(),
                 example.1
             );
         }
@@ -212,9 +172,9 @@ mod tests {
         ];
 
         for example in &examples {
-            let amplifier_controller = IntcodeProgram::from(example.0);
+            let amplifier_controller: IntcodeProgram = example.0.parse().unwrap();
             assert_eq!(
-                part2_calculate_highest_signal(&amplifier_controller),
+                part2_calculate_highest_signal(&amplifier_controller).unwrap(),
                 example.1
             );
         }
@@ -1,5 +1,6 @@
 //! --- Day 8: Space Image Format ---
 
+use crate::{Answer, Error, Result};
 use itertools::Itertools;
 use std::fmt;
 
@@ -31,12 +32,9 @@ const PIXEL_TRANSPARENT: u32 = 2;
 /// The image you received is 25 pixels wide and 6 pixels tall.
 ///
 /// To make sure the image wasn't corrupted during transmission, the Elves would like you to find the layer that contains the fewest 0 digits. On that layer, what is the number of 1 digits multiplied by the number of 2 digits?
-pub fn part1() {
-    let input = crate::common::read_stdin_to_string();
-    let layers = layers_from_input(input.as_str());
-
+pub fn part1(layers: &[Vec<u32>]) -> Result<Answer> {
     let fewest_zeros_layer = layers
-        .into_iter()
+        .iter()
         .map(|layer| (layer.iter().filter(|pixel| **pixel == 0).count(), layer))
         .fold1(|fewest_zeros_layer, layer| {
             if layer.0 < fewest_zeros_layer.0 {
@@ -45,7 +43,7 @@ pub fn part1() {
                 fewest_zeros_layer
             }
         })
-        .expect("No layers found")
+        .ok_or_else(|| Error::NoSolution("the image has no layers".into()))?
         .1;
 
     let number_of_ones_in_layer = fewest_zeros_layer
@@ -60,10 +58,7 @@ pub fn part1() {
 
     let result = number_of_ones_in_layer * number_of_twos_in_layer;
 
-    println!(
-        "On the layer that contains the fewest 0 digits, the number of 1 digits multiplied by the number of 2 digits: {}",
-        result
-    );
+    Ok(result.into())
 }
 
 /// Now you're ready to decode the image. The image is rendered by stacking the layers and aligning the pixels with the same positions in each layer. The digits indicate the color of the corresponding pixel: 0 is black, 1 is white, and 2 is transparent.
@@ -86,10 +81,12 @@ pub fn part1() {
 ///
 /// Then, the full image can be found by determining the top visible pixel in each position:
 ///
-///     The top-left pixel is black because the top layer is 0.
-///     The top-right pixel is white because the top layer is 2 (transparent), but the second layer is 1.
-///     The bottom-left pixel is white because the top two layers are 2, but the third layer is 1.
-///     The bottom-right pixel is black because the only visible pixel in that position is 0 (from layer 4).
+/// ```text
+/// The top-left pixel is black because the top layer is 0.
+/// The top-right pixel is white because the top layer is 2 (transparent), but the second layer is 1.
+/// The bottom-left pixel is white because the top two layers are 2, but the third layer is 1.
+/// The bottom-right pixel is black because the only visible pixel in that position is 0 (from layer 4).
+/// ```
 ///
 /// So, the final image looks like this:
 ///
@@ -97,10 +94,7 @@ pub fn part1() {
 /// 10
 ///
 /// What message is produced after decoding your image?
-pub fn part2() {
-    let input = crate::common::read_stdin_to_string();
-    let layers = layers_from_input(input.as_str());
-
+pub fn part2(layers: &[Vec<u32>]) -> Result<Answer> {
     let image_data = ImageData(
         (0..IMAGE_HEIGHT)
             .map(|y| {
@@ -117,21 +111,41 @@ pub fn part2() {
             .collect(),
     );
 
-    println!("The decoded image:\n{}", image_data);
+    Ok(image_data.to_string().into())
 }
 
-fn layers_from_input(input: &str) -> Vec<Vec<u32>> {
-    input
-        .trim()
-        .chars()
-        .map(|character| character.to_digit(10))
-        .map(|result| result.expect("Failed to parse input pixel as digit"))
-        .enumerate()
-        .group_by(|(index, _)| index / (IMAGE_WIDTH * IMAGE_HEIGHT))
-        .into_iter()
-        .map(|(_, layer)| layer.map(|(_, pixels)| pixels))
-        .map(|layer| layer.collect())
-        .collect()
+/// Parses the image in the puzzle input into its layers of pixels
+pub fn parse(input: &str) -> Result<Vec<Vec<u32>>> {
+    let image = input.trim();
+    let pixels = image
+        .char_indices()
+        .map(|(index, character)| match character.to_digit(10) {
+            Some(pixel @ PIXEL_BLACK..=PIXEL_TRANSPARENT) => Ok(pixel),
+            _ => Err(Error::parse(
+                input,
+                &image[index..],
+                format!("invalid pixel {:?}", character),
+            )),
+        })
+        .collect::<Result<Vec<u32>>>()?;
+
+    if pixels.len() % (IMAGE_WIDTH * IMAGE_HEIGHT) != 0 {
+        return Err(Error::parse(
+            input,
+            &image[image.len()..],
+            format!(
+                "expected whole {}x{} layers, found {} pixels",
+                IMAGE_WIDTH,
+                IMAGE_HEIGHT,
+                pixels.len()
+            ),
+        ));
+    }
+
+    Ok(pixels
+        .chunks(IMAGE_WIDTH * IMAGE_HEIGHT)
+        .map(|layer| layer.to_vec())
+        .collect())
 }
 
 struct ImageData(Vec<Vec<u32>>);
@@ -146,8 +160,8 @@ impl fmt::Display for ImageData {
                     .map(|pixel| match *pixel {
                         PIXEL_BLACK => ' ',
                         PIXEL_WHITE => '█',
-                        PIXEL_TRANSPARENT => '░',
-                        other => panic!("Invalid pixel valid: {}", other),
+                        // parse only accepts the three pixel colours
+                        _ => '░',
                     })
                     .collect::<String>()
             })
@@ -156,3 +170,23 @@ impl fmt::Display for ImageData {
         write!(f, "{}", rendered)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_image() {
+        let layer = "0".repeat(IMAGE_WIDTH * IMAGE_HEIGHT);
+
+        assert_eq!(
+            parse(&format!("{}3", &layer[1..])).unwrap_err().to_string(),
+            "invalid input at line 1, column 150: invalid pixel '3'"
+        );
+        assert_eq!(
+            parse(&format!("{}012", layer)).unwrap_err().to_string(),
+            "invalid input at line 1, column 154: expected whole 25x6 layers, found 153 pixels"
+        );
+        assert!(part1(&parse("").unwrap()).is_err());
+    }
+}
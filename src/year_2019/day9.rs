@@ -1,6 +1,12 @@
 //! --- Day 9: Sensor Boost ---
 
-use super::IntcodeComputer;
+use super::{IntcodeComputer, IntcodeProgram};
+use crate::{Answer, Error, Result};
+
+/// Parses the Intcode program in the puzzle input
+pub fn parse(input: &str) -> Result<IntcodeProgram> {
+    input.parse()
+}
 
 /// You've just said goodbye to the rebooted rover and left Mars when you receive a faint distress signal coming from the asteroid belt. It must be the Ceres monitoring station!
 ///
@@ -20,50 +26,46 @@ use super::IntcodeComputer;
 ///
 /// The relative base is modified with the relative base offset instruction:
 ///
-///     Opcode 9 adjusts the relative base by the value of its only parameter. The relative base increases (or decreases, if the value is negative) by the value of the parameter.
+/// ```text
+/// Opcode 9 adjusts the relative base by the value of its only parameter. The relative base increases (or decreases, if the value is negative) by the value of the parameter.
+/// ```
 ///
 /// For example, if the relative base is 2000, then after the instruction 109,19, the relative base would be 2019. If the next instruction were 204,-34, then the value at address 1985 would be output.
 ///
 /// Your Intcode computer will also need a few other capabilities:
 ///
-///     The computer's available memory should be much larger than the initial program. Memory beyond the initial program starts with the value 0 and can be read or written like any other memory. (It is invalid to try to access memory at a negative address, though.)
-///     The computer should have support for large numbers. Some instructions near the beginning of the BOOST program will verify this capability.
+/// ```text
+/// The computer's available memory should be much larger than the initial program. Memory beyond the initial program starts with the value 0 and can be read or written like any other memory. (It is invalid to try to access memory at a negative address, though.)
+/// The computer should have support for large numbers. Some instructions near the beginning of the BOOST program will verify this capability.
+/// ```
 ///
 /// Here are some example programs that use these features:
 ///
-///     109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99 takes no input and produces a copy of itself as output.
-///     1102,34915192,34915192,7,4,7,99,0 should output a 16-digit number.
-///     104,1125899906842624,99 should output the large number in the middle.
+/// ```text
+/// 109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99 takes no input and produces a copy of itself as output.
+/// 1102,34915192,34915192,7,4,7,99,0 should output a 16-digit number.
+/// 104,1125899906842624,99 should output the large number in the middle.
+/// ```
 ///
 /// The BOOST program will ask for a single input; run it in test mode by providing it the value 1. It will perform a series of checks on each opcode, output any opcodes (and the associated parameter modes) that seem to be functioning incorrectly, and finally output a BOOST keycode.
 ///
 /// Once your Intcode computer is fully functional, the BOOST program should report no malfunctioning opcodes when run in test mode; it should only output a single value, the BOOST keycode. What BOOST keycode does it produce?
-pub fn part1() {
-    let input = crate::common::read_stdin_to_string();
-    let mut computer = IntcodeComputer::from(input.as_str());
-
-    let input_tx = computer.create_input();
-    let output_rx = computer.create_output();
+pub fn part1(program: &IntcodeProgram) -> Result<Answer> {
+    let mut computer = IntcodeComputer::from(program);
 
     const TEST_MODE_ID: i64 = 1;
-    input_tx.send(TEST_MODE_ID).unwrap();
-
-    computer.run();
-
-    let result: Vec<i64> = output_rx.try_iter().collect();
-
-    println!(
-        "The program output: {:?}",
-        result
-            .iter()
-            .map(|integer| integer.to_string())
-            .collect::<Vec<_>>()
-            .join(",")
-    );
-    println!(
-        "The BOOST keycode produced: {}",
-        result.last().expect("No output")
-    );
+    let result = computer.run_with_input(vec![TEST_MODE_ID])?;
+
+    match result.as_slice() {
+        [keycode] => Ok((*keycode).into()),
+        [] => Err(Error::NoSolution(
+            "the BOOST program produced no output".into(),
+        )),
+        malfunctioning_opcodes => Err(Error::NoSolution(format!(
+            "the BOOST program reported malfunctioning opcodes: {:?}",
+            malfunctioning_opcodes
+        ))),
+    }
 }
 
 /// You now have a complete Intcode computer.
@@ -73,21 +75,17 @@ pub fn part1() {
 /// The program runs in sensor boost mode by providing the input instruction the value 2. Once run, it will boost the sensors automatically, but it might take a few seconds to complete the operation on slower hardware. In sensor boost mode, the program will output a single value: the coordinates of the distress signal.
 ///
 /// Run the BOOST program in sensor boost mode. What are the coordinates of the distress signal?
-pub fn part2() {
-    let input = crate::common::read_stdin_to_string();
-    let mut computer = IntcodeComputer::from(input.as_str());
-
-    let input_tx = computer.create_input();
-    let output_rx = computer.create_output();
+pub fn part2(program: &IntcodeProgram) -> Result<Answer> {
+    let mut computer = IntcodeComputer::from(program);
 
     const SENSOR_BOOST_MODE_ID: i64 = 2;
-    input_tx.send(SENSOR_BOOST_MODE_ID).unwrap();
-
-    computer.run();
+    let result = computer.run_with_input(vec![SENSOR_BOOST_MODE_ID])?;
 
-    let coordinates = output_rx.recv().unwrap();
+    let coordinates = result
+        .first()
+        .ok_or_else(|| Error::NoSolution("the BOOST program produced no output".into()))?;
 
-    println!("The coordinates of the distress signal: {}", coordinates);
+    Ok((*coordinates).into())
 }
 
 #[cfg(test)]
@@ -116,10 +114,10 @@ mod tests {
             99,
         );
 
-        let mut computer = IntcodeComputer::from(example.0);
+        let mut computer: IntcodeComputer = example.0.parse().unwrap();
         let output_rx = computer.create_output();
 
-        computer.run();
+        computer.run().unwrap();
 
         assert_eq!(output_rx.recv().unwrap(), example.1);
         assert_eq!(output_rx.recv().unwrap(), example.2);
@@ -147,10 +145,10 @@ mod tests {
         ];
 
         for example in &examples {
-            let mut computer = IntcodeComputer::from(example.0);
+            let mut computer: IntcodeComputer = example.0.parse().unwrap();
             let output_rx = computer.create_output();
 
-            computer.run();
+            computer.run().unwrap();
 
             assert_eq!(output_rx.recv().unwrap(), example.1);
         }
@@ -0,0 +1,222 @@
+//! Talking to Intcode programs which use ASCII for their input and output.
+//!
+//! Input is sent a line at a time, as character codes followed by a newline (10). Output values from 0 to 127 are
+//! collected as text; anything else (usually a large integer, once the program is done talking) is kept separately as
+//! the program's answer.
+use super::{IntcodeComputer, IntcodeError, IntcodeEvent};
+use crate::{Error, Result};
+use std::io::{BufRead, Write};
+
+/// Why an `IntcodeAscii` program stopped and handed control back to its caller
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsciiEvent {
+    /// The program is waiting for a line of input
+    NeedsInput,
+
+    Halted,
+}
+
+#[derive(Debug, Clone)]
+pub struct IntcodeAscii {
+    computer: IntcodeComputer,
+
+    /// Text output since it was last taken
+    text: String,
+
+    /// Output values which aren't ASCII, in the order they were output
+    values: Vec<i64>,
+}
+
+impl IntcodeAscii {
+    pub fn new(computer: IntcodeComputer) -> Self {
+        Self {
+            computer,
+            text: String::new(),
+            values: Vec::new(),
+        }
+    }
+
+    pub fn computer(&self) -> &IntcodeComputer {
+        &self.computer
+    }
+
+    pub fn computer_mut(&mut self) -> &mut IntcodeComputer {
+        &mut self.computer
+    }
+
+    /// Queues a line of input, adding the newline
+    pub fn send_line(&mut self, line: &str) {
+        for byte in line.bytes().chain(Some(b'\n')) {
+            self.computer.push_input(i64::from(byte));
+        }
+    }
+
+    /// Runs the program until it needs input or halts, collecting its output
+    pub fn run_until_event(&mut self) -> Result<AsciiEvent, IntcodeError> {
+        loop {
+            match self.computer.run_until_event()? {
+                IntcodeEvent::Output(value) if (0..128).contains(&value) => {
+                    self.text.push(value as u8 as char)
+                }
+                IntcodeEvent::Output(value) => self.values.push(value),
+                IntcodeEvent::NeedsInput => return Ok(AsciiEvent::NeedsInput),
+                IntcodeEvent::Halted => return Ok(AsciiEvent::Halted),
+            }
+        }
+    }
+
+    /// Runs the program, sending it the lines of `script` one at a time as it asks for input.
+    ///
+    /// Stops when the program halts, or when it needs input after the script has run out.
+    pub fn run_script<S: AsRef<str>>(
+        &mut self,
+        script: impl IntoIterator<Item = S>,
+    ) -> Result<AsciiEvent, IntcodeError> {
+        let mut script = script.into_iter();
+
+        loop {
+            match self.run_until_event()? {
+                AsciiEvent::NeedsInput => match script.next() {
+                    Some(line) => self.send_line(line.as_ref()),
+                    None => return Ok(AsciiEvent::NeedsInput),
+                },
+                AsciiEvent::Halted => return Ok(AsciiEvent::Halted),
+            }
+        }
+    }
+
+    /// Runs the program in a terminal session: its text is written to `output` as it's produced, and a line is read
+    /// from `input` whenever it needs input.
+    ///
+    /// Stops when the program halts or `input` runs out, and returns the program's answer, if it output one.
+    pub fn run_interactive(
+        &mut self,
+        mut input: impl BufRead,
+        mut output: impl Write,
+    ) -> Result<Option<i64>> {
+        let io_error = |source| Error::Io {
+            context: "ASCII session failed".into(),
+            source,
+        };
+
+        loop {
+            let event = self.run_until_event()?;
+            output
+                .write_all(self.take_text().as_bytes())
+                .and_then(|()| output.flush())
+                .map_err(io_error)?;
+
+            if event == AsciiEvent::Halted {
+                break;
+            }
+
+            let mut line = String::new();
+            if input.read_line(&mut line).map_err(io_error)? == 0 {
+                break;
+            }
+            self.send_line(line.trim_end_matches(&['\r', '\n'][..]));
+        }
+
+        Ok(self.answer())
+    }
+
+    /// Takes the text output since the last time it was taken
+    pub fn take_text(&mut self) -> String {
+        std::mem::take(&mut self.text)
+    }
+
+    /// Takes the complete lines of text output since they were last taken, leaving any incomplete last line
+    pub fn take_lines(&mut self) -> Vec<String> {
+        let complete = self.text.rfind('\n').map_or(0, |index| index + 1);
+        let rest = self.text.split_off(complete);
+
+        std::mem::replace(&mut self.text, rest)
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    /// Every output value which wasn't ASCII
+    pub fn values(&self) -> &[i64] {
+        &self.values
+    }
+
+    /// The last output value which wasn't ASCII
+    pub fn answer(&self) -> Option<i64> {
+        self.values.last().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::year_2019::assemble;
+
+    /// Asks for a line and echoes it back in a sentence, until it's sent an empty line. Then it outputs 1000.
+    const ECHO: &str = "
+        prompt: OUT #62
+                OUT #10
+        read:   IN -> [char]
+                EQ [char], #10 -> [done]
+                JNZ [done], #reply
+                ADD [length], #1 -> [length]
+        store:  ADD [char], #0 -> [buffer]
+                ADD [store+3], #1 -> [store+3]
+                JNZ #1, #read
+        reply:  JZ [length], #end
+                OUT #58
+                OUT #32
+                ADD #buffer, #0 -> [print+1]
+        print:  OUT [buffer]
+                ADD [print+1], #1 -> [print+1]
+                ADD [length], #-1 -> [length]
+                JNZ [length], #print
+                OUT #10
+                ADD #buffer, #0 -> [store+3]
+                JNZ #1, #prompt
+        end:    OUT #1000
+                HALT
+        char:   DATA 0
+        done:   DATA 0
+        length: DATA 0
+        buffer: DATA 0";
+
+    #[test]
+    fn test_script() {
+        let mut ascii = IntcodeAscii::new(IntcodeComputer::from(&assemble(ECHO).unwrap()));
+
+        assert_eq!(
+            ascii.run_script(["hi", "hello"]),
+            Ok(AsciiEvent::NeedsInput)
+        );
+        assert_eq!(ascii.take_lines(), vec![">", ": hi", ">", ": hello", ">"]);
+        assert_eq!(ascii.answer(), None);
+
+        assert_eq!(ascii.run_script([""]), Ok(AsciiEvent::Halted));
+        assert_eq!(ascii.take_text(), "");
+        assert_eq!(ascii.answer(), Some(1000));
+    }
+
+    #[test]
+    fn test_take_lines() {
+        let mut ascii = IntcodeAscii::new("104,65,104,10,104,66,99".parse().unwrap());
+
+        assert_eq!(ascii.run_until_event(), Ok(AsciiEvent::Halted));
+        assert_eq!(ascii.take_lines(), vec!["A"]);
+        assert_eq!(ascii.take_text(), "B");
+    }
+
+    #[test]
+    fn test_interactive() {
+        let mut ascii = IntcodeAscii::new(IntcodeComputer::from(&assemble(ECHO).unwrap()));
+        let mut output = Vec::new();
+
+        assert_eq!(
+            ascii
+                .run_interactive(&b"abc\r\n\n"[..], &mut output)
+                .unwrap(),
+            Some(1000)
+        );
+        assert_eq!(String::from_utf8(output).unwrap(), ">\n: abc\n>\n");
+    }
+}
@@ -0,0 +1,404 @@
+//! Builds Intcode programs from assembly source.
+//!
+//! The syntax matches the listings printed by the disassembler, so a listing can be edited and assembled again:
+//!
+//! ```text
+//! ; doubles each input until it reads a zero
+//! loop:   IN -> [value]
+//!         JZ [value], #end
+//!         MUL [value], #2 -> [value]
+//!         OUT [value]
+//!         JNZ #1, #loop
+//! end:    HALT
+//! value:  DATA 0
+//! ```
+//!
+//! - Each line holds at most one instruction or `DATA` directive, optionally preceded by one or more `label:`s.
+//!   Numeric labels (like the `0004:` addresses in a listing) must match the address they label.
+//! - Parameters are written `[address]` for position mode, `#value` for immediate mode and `[rb+offset]` (or
+//!   `[rb-offset]`, or `[rb]`) for relative mode. The parameter an instruction writes to may be separated with `->`
+//!   instead of a comma.
+//! - Addresses, values and offsets are integers, labels, or a label plus or minus an integer (`value+1`).
+//! - `DATA` emits its comma-separated values as they are.
+//! - Everything after a `;` is a comment. Mnemonics are case-insensitive, labels are not.
+use super::intcode_disassembler::MNEMONICS;
+use super::IntcodeProgram;
+use crate::{Error, Result};
+use std::collections::HashMap;
+
+/// Assembles `source` into an Intcode program
+pub fn assemble(source: &str) -> Result<IntcodeProgram> {
+    let mut statements = Vec::new();
+    let mut labels = HashMap::new();
+    let mut address = 0;
+
+    for line in source.lines() {
+        let mut code = line.split(';').next().unwrap_or_default().trim();
+
+        while let Some((label, rest)) = code.split_once(':') {
+            let label = label.trim();
+            if label.chars().all(|c| c.is_ascii_digit()) && !label.is_empty() {
+                if label.parse() != Ok(address) {
+                    return Err(Error::parse(
+                        source,
+                        label,
+                        format!(
+                            "address {} doesn't match assembled address {}",
+                            label, address
+                        ),
+                    ));
+                }
+            } else if !is_identifier(label) {
+                return Err(Error::parse(
+                    source,
+                    label,
+                    format!("invalid label {:?}", label),
+                ));
+            } else if labels.insert(label, address).is_some() {
+                return Err(Error::parse(
+                    source,
+                    label,
+                    format!("label {} is already defined", label),
+                ));
+            }
+            code = rest.trim();
+        }
+
+        if !code.is_empty() {
+            let statement = Statement::parse(source, code)?;
+            address += statement.length();
+            statements.push(statement);
+        }
+    }
+
+    let mut data = Vec::with_capacity(address);
+    for statement in statements {
+        match statement {
+            Statement::Instruction(opcode, operands) => {
+                let modes = operands
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (mode, _))| mode * 10_i64.pow(2 + i as u32))
+                    .sum::<i64>();
+                data.push(opcode + modes);
+                for (_, value) in operands {
+                    data.push(value.resolve(source, &labels)?);
+                }
+            }
+            Statement::Data(values) => {
+                for value in values {
+                    data.push(value.resolve(source, &labels)?);
+                }
+            }
+        }
+    }
+
+    Ok(IntcodeProgram::from(data))
+}
+
+#[derive(Debug)]
+enum Statement<'a> {
+    /// An opcode, and the mode and value of each parameter
+    Instruction(i64, Vec<(i64, Expression<'a>)>),
+
+    Data(Vec<Expression<'a>>),
+}
+
+impl<'a> Statement<'a> {
+    fn parse(source: &'a str, code: &'a str) -> Result<Self> {
+        let (mnemonic, operands) = code
+            .split_once(char::is_whitespace)
+            .map_or((code, ""), |(mnemonic, operands)| {
+                (mnemonic, operands.trim())
+            });
+
+        if mnemonic.eq_ignore_ascii_case("DATA") {
+            let values = split_list(operands)
+                .into_iter()
+                .map(|value| Expression::parse(source, value))
+                .collect::<Result<Vec<_>>>()?;
+            if values.is_empty() {
+                return Err(Error::parse(
+                    source,
+                    mnemonic,
+                    "DATA needs at least one value",
+                ));
+            }
+            return Ok(Self::Data(values));
+        }
+
+        let &(opcode, mnemonic, parameters, writes) = MNEMONICS
+            .iter()
+            .find(|(_, name, ..)| name.eq_ignore_ascii_case(mnemonic))
+            .ok_or_else(|| {
+                Error::parse(source, mnemonic, format!("unknown mnemonic {:?}", mnemonic))
+            })?;
+
+        let (inputs, destination) = match operands.split_once("->") {
+            Some(_) if !writes => {
+                return Err(Error::parse(
+                    source,
+                    operands,
+                    format!("{} doesn't write to a parameter", mnemonic),
+                ))
+            }
+            Some((inputs, destination)) => (inputs.trim(), Some(destination.trim())),
+            None => (operands, None),
+        };
+        let mut operands = split_list(inputs);
+        operands.extend(destination);
+
+        if operands.len() != parameters {
+            return Err(Error::parse(
+                source,
+                code,
+                format!(
+                    "{} takes {} parameters, found {}",
+                    mnemonic,
+                    parameters,
+                    operands.len()
+                ),
+            ));
+        }
+
+        let operands = operands
+            .into_iter()
+            .enumerate()
+            .map(|(i, operand)| {
+                let (mode, value) = parse_operand(source, operand)?;
+                if mode == 1 && writes && i + 1 == parameters {
+                    return Err(Error::parse(
+                        source,
+                        operand,
+                        "can't write to an immediate mode parameter",
+                    ));
+                }
+                Ok((mode, value))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self::Instruction(opcode, operands))
+    }
+
+    /// How many values the statement assembles to
+    fn length(&self) -> usize {
+        match self {
+            Self::Instruction(_, operands) => 1 + operands.len(),
+            Self::Data(values) => values.len(),
+        }
+    }
+}
+
+/// Parses an `[address]`, `#value` or `[rb+offset]` parameter into its mode and value
+fn parse_operand<'a>(source: &'a str, operand: &'a str) -> Result<(i64, Expression<'a>)> {
+    if let Some(value) = operand.strip_prefix('#') {
+        return Ok((1, Expression::parse(source, value.trim())?));
+    }
+
+    let inner = operand
+        .strip_prefix('[')
+        .and_then(|operand| operand.strip_suffix(']'))
+        .map(str::trim)
+        .ok_or_else(|| {
+            Error::parse(
+                source,
+                operand,
+                format!(
+                    "expected [address], #value or [rb+offset], found {:?}",
+                    operand
+                ),
+            )
+        })?;
+
+    // `[rbx]` is the position of a label called `rbx`, not a relative parameter
+    let offset = inner
+        .strip_prefix("rb")
+        .map(str::trim_start)
+        .filter(|offset| offset.is_empty() || offset.starts_with(['+', '-']));
+
+    match offset {
+        Some("") => Ok((2, Expression::number(0))),
+        Some(offset) => {
+            let offset = offset.strip_prefix('+').map_or(offset, str::trim_start);
+            Ok((2, Expression::parse(source, offset)?))
+        }
+        None => Ok((0, Expression::parse(source, inner)?)),
+    }
+}
+
+/// An integer, or a label plus or minus an integer, which is resolved once every label's address is known
+#[derive(Debug)]
+struct Expression<'a> {
+    negative: bool,
+    label: Option<&'a str>,
+    offset: i64,
+}
+
+impl<'a> Expression<'a> {
+    fn number(value: i64) -> Self {
+        Self {
+            negative: false,
+            label: None,
+            offset: value,
+        }
+    }
+
+    fn parse(source: &'a str, text: &'a str) -> Result<Self> {
+        let error = || Error::parse(source, text, format!("invalid value {:?}", text));
+
+        if let Ok(value) = text.parse() {
+            return Ok(Self::number(value));
+        }
+
+        let (negative, rest) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest.trim_start()),
+            None => (false, text),
+        };
+        if let Ok(value) = rest.parse::<i64>() {
+            return Ok(Self::number(if negative { -value } else { value }));
+        }
+        let (label, offset) = match rest.find(['+', '-']) {
+            Some(index) => {
+                let offset = rest[index + 1..]
+                    .trim()
+                    .parse::<i64>()
+                    .map_err(|_| error())?;
+                let offset = if rest[index..].starts_with('-') {
+                    -offset
+                } else {
+                    offset
+                };
+                (rest[..index].trim(), offset)
+            }
+            None => (rest, 0),
+        };
+
+        if !is_identifier(label) {
+            return Err(error());
+        }
+
+        Ok(Self {
+            negative,
+            label: Some(label),
+            offset,
+        })
+    }
+
+    fn resolve(&self, source: &str, labels: &HashMap<&str, usize>) -> Result<i64> {
+        let base = match self.label {
+            Some(label) => *labels
+                .get(label)
+                .ok_or_else(|| Error::parse(source, label, format!("undefined label {}", label)))?
+                as i64,
+            None => 0,
+        };
+        let value = base + self.offset;
+
+        Ok(if self.negative { -value } else { value })
+    }
+}
+
+/// Splits a comma-separated list, which may be empty
+fn split_list(list: &str) -> Vec<&str> {
+    if list.is_empty() {
+        Vec::new()
+    } else {
+        list.split(',').map(str::trim).collect()
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::year_2019::{disassemble, IntcodeComputer};
+
+    #[test]
+    fn test_assemble() {
+        let program = assemble(
+            "; doubles each input until it reads a zero
+             loop:   IN -> [value]
+                     JZ [value], #end
+                     MUL [value], #2 -> [value]
+                     out [value]
+                     JNZ #1, #loop
+             end:    HALT
+             value:  DATA 0",
+        )
+        .unwrap();
+
+        assert_eq!(
+            program.data_serialized(),
+            "3,15,1006,15,14,1002,15,2,15,4,15,1105,1,0,99,0"
+        );
+
+        let mut computer = IntcodeComputer::from(&program);
+        assert_eq!(
+            computer.run_with_input(vec![4, 21, 0]).unwrap(),
+            vec![8, 42]
+        );
+
+        assert_eq!(
+            assemble("ARB #-3\nOUT [rb - 1]\nADD [rb+x], [x-1], [rb]\nx: DATA -x, x+1")
+                .unwrap()
+                .data_serialized(),
+            "109,-3,204,-1,20201,8,7,0,-8,9"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let quine: IntcodeProgram = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99"
+            .parse()
+            .unwrap();
+        let data: IntcodeProgram = "99,42,1099,301,0,0,0,11101,1,2,3,-5,9,10,11,12,1,2"
+            .parse()
+            .unwrap();
+
+        for program in &[quine, data] {
+            assert_eq!(assemble(&disassemble(program)).unwrap(), *program);
+        }
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        let message = |source| match assemble(source) {
+            Err(Error::Parse {
+                line,
+                column,
+                message,
+            }) => format!("{}:{}: {}", line, column, message),
+            other => panic!("expected a parse error, got {:?}", other),
+        };
+
+        assert_eq!(message("NOP"), "1:1: unknown mnemonic \"NOP\"");
+        assert_eq!(
+            message("HALT\nADD #1, #2"),
+            "2:1: ADD takes 3 parameters, found 2"
+        );
+        assert_eq!(
+            message("IN -> #5"),
+            "1:7: can't write to an immediate mode parameter"
+        );
+        assert_eq!(message("OUT [missing]"), "1:6: undefined label missing");
+        assert_eq!(
+            message("OUT 5"),
+            "1:5: expected [address], #value or [rb+offset], found \"5\""
+        );
+        assert_eq!(
+            message("a: HALT\na: HALT"),
+            "2:1: label a is already defined"
+        );
+        assert_eq!(
+            message("0000: HALT\n0002: HALT"),
+            "2:1: address 0002 doesn't match assembled address 1"
+        );
+    }
+}
@@ -0,0 +1,465 @@
+//! An Intcode computer whose values are arbitrary-precision integers.
+//!
+//! `IntcodeComputer` stores 64-bit values, and stops with `IntcodeErrorKind::Overflow` when an addition or
+//! multiplication doesn't fit. Programs which need larger values can run on a `BigIntcodeComputer` instead, which
+//! decodes the same `IntcodeInstruction`s and reports faults with the same `IntcodeError`. It's slower, and has none
+//! of the debugging features of `IntcodeComputer`, but supports the same memory, instruction and time limits.
+//!
+//! Values in an `IntcodeError` are 64-bit, so any which don't fit are reported as `i64::MIN` or `i64::MAX`.
+use super::intcode_computer::{
+    IntcodeInstruction, IntcodeParameter, IntcodeValue, TIME_LIMIT_CHECK_INTERVAL,
+};
+use super::{IntcodeError, IntcodeErrorKind, IntcodeProgram};
+use crate::{Error, Result};
+use num_bigint::{BigInt, Sign};
+use num_traits::{ToPrimitive, Zero};
+use std::collections::{BTreeMap, VecDeque};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Why a running `BigIntcodeComputer` stopped and handed control back to its caller
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BigIntcodeEvent {
+    /// The program output a value
+    Output(BigInt),
+
+    /// The program needs input, and none is pending
+    NeedsInput,
+
+    Halted,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BigIntcodeComputer {
+    /// The program as it was loaded, and any changes made to it since
+    memory: Vec<BigInt>,
+
+    /// Values written beyond the end of the program
+    sparse_memory: BTreeMap<usize, BigInt>,
+
+    instruction_pointer: usize,
+    relative_base: BigInt,
+    pending_input: VecDeque<BigInt>,
+    memory_limit: Option<usize>,
+    instruction_limit: Option<u64>,
+    time_limit: Option<Duration>,
+    instructions_executed: u64,
+}
+
+impl BigIntcodeComputer {
+    pub fn new(memory: Vec<BigInt>) -> Self {
+        Self {
+            memory,
+            ..Self::default()
+        }
+    }
+
+    pub fn get(&self, address: usize) -> BigInt {
+        match self.memory.get(address) {
+            Some(value) => value.clone(),
+            None => self
+                .sparse_memory
+                .get(&address)
+                .cloned()
+                .unwrap_or_else(BigInt::zero),
+        }
+    }
+
+    pub fn instruction_pointer(&self) -> usize {
+        self.instruction_pointer
+    }
+
+    pub fn relative_base(&self) -> &BigInt {
+        &self.relative_base
+    }
+
+    /// How many instructions the program has executed since it was loaded
+    pub fn instructions_executed(&self) -> u64 {
+        self.instructions_executed
+    }
+
+    /// Queues a value for the program to read
+    pub fn push_input(&mut self, value: impl Into<BigInt>) {
+        self.pending_input.push_back(value.into());
+    }
+
+    /// Limits how many values the program's memory may hold, or removes the limit.
+    ///
+    /// A write to an address not yet in memory fails with `IntcodeErrorKind::MemoryLimitExceeded` if memory is full.
+    /// Only the number of values is limited, not how large each one grows.
+    pub fn set_memory_limit(&mut self, limit: Option<usize>) {
+        self.memory_limit = limit;
+    }
+
+    /// Limits how many instructions the program may execute, or removes the limit.
+    ///
+    /// Executing any more fails with `IntcodeErrorKind::InstructionLimitExceeded`.
+    pub fn set_instruction_limit(&mut self, limit: Option<u64>) {
+        self.instruction_limit = limit;
+    }
+
+    /// Limits how long each call to `run_until_event` (and so `run_with_input`) may take, or removes the limit.
+    ///
+    /// Running for any longer fails with `IntcodeErrorKind::TimedOut`.
+    pub fn set_time_limit(&mut self, limit: Option<Duration>) {
+        self.time_limit = limit;
+    }
+
+    /// Runs the program with `input` until it halts, and returns everything it output
+    pub fn run_with_input(&mut self, input: Vec<BigInt>) -> Result<Vec<BigInt>, IntcodeError> {
+        self.pending_input.extend(input);
+
+        let mut output = Vec::new();
+        loop {
+            match self.run_until_event()? {
+                BigIntcodeEvent::Output(value) => output.push(value),
+                BigIntcodeEvent::NeedsInput => {
+                    return Err(self.error(None, IntcodeErrorKind::InputUnavailable))
+                }
+                BigIntcodeEvent::Halted => return Ok(output),
+            }
+        }
+    }
+
+    /// Runs the program until it outputs a value, needs input which isn't pending, or halts
+    pub fn run_until_event(&mut self) -> Result<BigIntcodeEvent, IntcodeError> {
+        let deadline = self.time_limit.map(|limit| (Instant::now() + limit, limit));
+
+        loop {
+            if let Some((deadline, limit)) = deadline {
+                if self.instructions_executed % TIME_LIMIT_CHECK_INTERVAL == 0
+                    && Instant::now() > deadline
+                {
+                    return Err(self.error(None, IntcodeErrorKind::TimedOut(limit)));
+                }
+            }
+
+            if let Some(event) = self.step()? {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Executes the next instruction, returning what happened if the program output, needs input or halted
+    pub fn step(&mut self) -> Result<Option<BigIntcodeEvent>, IntcodeError> {
+        if let Some(limit) = self.instruction_limit {
+            if self.instructions_executed >= limit {
+                return Err(self.error(None, IntcodeErrorKind::InstructionLimitExceeded(limit)));
+            }
+        }
+
+        let instruction = IntcodeInstruction::decode_with(
+            self.instruction_pointer,
+            |address| self.get(address),
+            |parameter, kind| self.error(parameter, kind),
+        )?;
+
+        let mut event = None;
+        let mut jump = None;
+
+        match &instruction {
+            IntcodeInstruction::Add(one, two, output) => {
+                let sum = self.read(one)? + self.read(two)?;
+                self.write(output, sum)?;
+            }
+            IntcodeInstruction::Multiply(one, two, output) => {
+                let product = self.read(one)? * self.read(two)?;
+                self.write(output, product)?;
+            }
+            IntcodeInstruction::Input(to) => {
+                // worked out first, so an invalid address doesn't use up any input
+                let address = self.address_of(to)?;
+                match self.pending_input.pop_front() {
+                    Some(value) => {
+                        if let Err(error) = self.write_to(address, value.clone()) {
+                            self.pending_input.push_front(value);
+                            return Err(error);
+                        }
+                    }
+                    None => return Ok(Some(BigIntcodeEvent::NeedsInput)),
+                }
+            }
+            IntcodeInstruction::Output(from) => {
+                event = Some(BigIntcodeEvent::Output(self.read(from)?));
+            }
+            IntcodeInstruction::JumpIfTrue(test, jump_to)
+            | IntcodeInstruction::JumpIfFalse(test, jump_to) => {
+                let jump_if_zero = matches!(instruction, IntcodeInstruction::JumpIfFalse(..));
+                if self.read(test)?.is_zero() == jump_if_zero {
+                    let target = self.read(jump_to)?;
+                    jump = Some(self.address(&target, Some(&target))?);
+                }
+            }
+            IntcodeInstruction::LessThan(one, two, output) => {
+                let result = BigInt::from((self.read(one)? < self.read(two)?) as i64);
+                self.write(output, result)?;
+            }
+            IntcodeInstruction::Equals(one, two, output) => {
+                let result = BigInt::from((self.read(one)? == self.read(two)?) as i64);
+                self.write(output, result)?;
+            }
+            IntcodeInstruction::RelativeBaseOffset(offset) => {
+                self.relative_base += self.read(offset)?;
+            }
+            IntcodeInstruction::Halt => return Ok(Some(BigIntcodeEvent::Halted)),
+        }
+
+        // an instruction at the very end of memory has no next instruction to move on to
+        let next_instruction = self.instruction_pointer.checked_add(instruction.length());
+        self.instruction_pointer = jump
+            .or(next_instruction)
+            .ok_or_else(|| self.error(None, IntcodeErrorKind::Overflow))?;
+        self.instructions_executed += 1;
+        Ok(event)
+    }
+
+    /// The address a parameter of the current instruction refers to
+    fn address_of(&self, parameter: &IntcodeParameter<BigInt>) -> Result<usize, IntcodeError> {
+        match parameter {
+            IntcodeParameter::Position(address) => Ok(*address),
+            IntcodeParameter::Value(value) => {
+                Err(self.error(Some(value), IntcodeErrorKind::ImmediateModeWrite))
+            }
+            IntcodeParameter::Relative(offset) => {
+                let address = &self.relative_base + offset;
+                self.address(&address, Some(offset))
+            }
+        }
+    }
+
+    fn address(&self, value: &BigInt, parameter: Option<&BigInt>) -> Result<usize, IntcodeError> {
+        value
+            .to_address()
+            .map_err(|kind| self.error(parameter, kind))
+    }
+
+    fn read(&self, parameter: &IntcodeParameter<BigInt>) -> Result<BigInt, IntcodeError> {
+        match parameter {
+            IntcodeParameter::Value(value) => Ok(value.clone()),
+            _ => Ok(self.get(self.address_of(parameter)?)),
+        }
+    }
+
+    /// Writes to the address a parameter of the current instruction refers to
+    fn write(
+        &mut self,
+        parameter: &IntcodeParameter<BigInt>,
+        value: BigInt,
+    ) -> Result<(), IntcodeError> {
+        let address = self.address_of(parameter)?;
+        self.write_to(address, value)
+    }
+
+    /// Writes to memory on behalf of the current instruction, unless memory would grow past its limit
+    fn write_to(&mut self, address: usize, value: BigInt) -> Result<(), IntcodeError> {
+        if let Some(limit) = self.memory_limit {
+            let size = self.memory.len() + self.sparse_memory.len();
+            let grows = address >= self.memory.len() && !self.sparse_memory.contains_key(&address);
+            if grows && size >= limit {
+                return Err(self.error(None, IntcodeErrorKind::MemoryLimitExceeded(address)));
+            }
+        }
+
+        match self.memory.get_mut(address) {
+            Some(stored) => *stored = value,
+            None => {
+                self.sparse_memory.insert(address, value);
+            }
+        }
+        Ok(())
+    }
+
+    /// An error caused by the current instruction
+    fn error(&self, parameter: Option<&BigInt>, kind: IntcodeErrorKind) -> IntcodeError {
+        IntcodeError {
+            instruction_pointer: self.instruction_pointer,
+            instruction_header: saturate(&self.get(self.instruction_pointer)),
+            parameter: parameter.map(saturate),
+            kind,
+        }
+    }
+}
+
+impl IntcodeValue for BigInt {
+    fn digit(&self, position: u32) -> i64 {
+        ((self / BigInt::from(10).pow(position)) % 10_u32)
+            .to_i64()
+            .expect("a remainder of 10 fits")
+    }
+
+    fn to_address(&self) -> Result<usize, IntcodeErrorKind> {
+        match self.to_usize() {
+            Some(address) => Ok(address),
+            None if self.sign() == Sign::Minus => {
+                Err(IntcodeErrorKind::NegativeAddress(saturate(self)))
+            }
+            None => Err(IntcodeErrorKind::Overflow),
+        }
+    }
+}
+
+/// Converts a value to 64 bits, rounding any which don't fit to `i64::MIN` or `i64::MAX`
+fn saturate(value: &BigInt) -> i64 {
+    value.to_i64().unwrap_or(match value.sign() {
+        Sign::Minus => i64::MIN,
+        _ => i64::MAX,
+    })
+}
+
+impl From<&IntcodeProgram> for BigIntcodeComputer {
+    fn from(program: &IntcodeProgram) -> Self {
+        let mut computer = Self::new(program.data().iter().map(|&value| value.into()).collect());
+        computer.sparse_memory = program
+            .sparse_data()
+            .map(|(address, value)| (address, value.into()))
+            .collect();
+        computer
+    }
+}
+
+impl FromStr for BigIntcodeComputer {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        let memory = string
+            .trim()
+            .split(',')
+            .map(str::trim)
+            .map(|integer| {
+                integer.parse::<BigInt>().map_err(|_| {
+                    Error::parse(
+                        string,
+                        integer,
+                        format!("invalid Intcode integer {:?}", integer),
+                    )
+                })
+            })
+            .collect::<Result<Vec<BigInt>>>()?;
+
+        Ok(Self::new(memory))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::year_2019::IntcodeComputer;
+
+    fn run(program: &str, input: Vec<i64>) -> Result<Vec<BigInt>, IntcodeError> {
+        let mut computer: BigIntcodeComputer = program.parse().unwrap();
+        computer.run_with_input(input.into_iter().map(BigInt::from).collect())
+    }
+
+    fn big(value: &str) -> BigInt {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_day9_examples() {
+        let quine = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+        assert_eq!(
+            run(quine, vec![]).unwrap(),
+            quine.split(',').map(big).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            run("1102,34915192,34915192,7,4,7,99,0", vec![]),
+            Ok(vec![big("1219070632396864")])
+        );
+
+        // compares its input with 8, like the day 5 examples
+        assert_eq!(run("3,9,8,9,10,9,4,9,99,-1,8", vec![8]), Ok(vec![big("1")]));
+    }
+
+    #[test]
+    fn test_large_values() {
+        let program = "1102,9223372036854775807,9223372036854775807,7,4,7,99,0";
+        assert_eq!(
+            program
+                .parse::<IntcodeComputer>()
+                .unwrap()
+                .run()
+                .unwrap_err()
+                .kind,
+            IntcodeErrorKind::Overflow
+        );
+        assert_eq!(
+            run(program, vec![]),
+            Ok(vec![big("85070591730234615847396907784232501249")])
+        );
+
+        // doubles its input until the result no longer fits in 64 bits, then outputs it
+        let program = "3,20,1002,20,2,20,1007,20,9223372036854775807,21,1005,21,2,4,20,99";
+        assert_eq!(run(program, vec![3]), Ok(vec![big("13835058055282163712")]));
+
+        assert_eq!(
+            run("1,0,0,99999999999999999999,99", vec![])
+                .unwrap_err()
+                .kind,
+            IntcodeErrorKind::Overflow
+        );
+        assert_eq!(
+            run("3,0,99", vec![]).unwrap_err().kind,
+            IntcodeErrorKind::InputUnavailable
+        );
+    }
+
+    #[test]
+    fn test_end_of_memory() {
+        // writes an ADD to the last address, whose parameters would be past it, and jumps to it
+        let program = "1101,0,1,18446744073709551615,1105,1,18446744073709551615";
+        assert_eq!(
+            run(program, vec![]).unwrap_err(),
+            IntcodeError {
+                instruction_pointer: usize::MAX,
+                instruction_header: 1,
+                parameter: None,
+                kind: IntcodeErrorKind::Overflow,
+            }
+        );
+
+        // writes an OUT to the second-to-last address, which has no instruction after it, and jumps to it
+        let program = "1101,0,4,18446744073709551614,1105,1,18446744073709551614";
+        assert_eq!(
+            run(program, vec![]).unwrap_err().kind,
+            IntcodeErrorKind::Overflow
+        );
+
+        // a HALT at the last address still halts
+        let program = "1101,0,99,18446744073709551615,1105,1,18446744073709551615";
+        assert_eq!(run(program, vec![]), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_limits() {
+        // squares a value forever, which would otherwise exhaust memory
+        let square_forever = "1102,2,2,9,1002,9,9,9,1105,1,4";
+
+        let mut computer: BigIntcodeComputer = square_forever.parse().unwrap();
+        computer.set_instruction_limit(Some(20));
+        assert_eq!(
+            computer.run_until_event().unwrap_err().kind,
+            IntcodeErrorKind::InstructionLimitExceeded(20)
+        );
+        assert_eq!(computer.instructions_executed(), 20);
+
+        let mut computer: BigIntcodeComputer = "1105,1,0".parse().unwrap();
+        computer.set_time_limit(Some(Duration::from_millis(10)));
+        assert_eq!(
+            computer.run_until_event().unwrap_err().kind,
+            IntcodeErrorKind::TimedOut(Duration::from_millis(10))
+        );
+
+        let mut computer: BigIntcodeComputer = "1101,7,8,10,1101,7,8,20,99".parse().unwrap();
+        computer.set_memory_limit(Some(10));
+        assert_eq!(
+            computer.run_until_event().unwrap_err(),
+            IntcodeError {
+                instruction_pointer: 4,
+                instruction_header: 1101,
+                parameter: None,
+                kind: IntcodeErrorKind::MemoryLimitExceeded(20),
+            }
+        );
+        assert_eq!(computer.get(10), big("15"));
+    }
+}
@@ -1,14 +1,53 @@
-use std::convert::TryInto;
-use std::sync::mpsc::{self, Receiver, Sender};
-use std::thread;
+use super::intcode_error::{IntcodeError, IntcodeErrorKind};
+use super::intcode_io::{InputIter, IntcodeInput, IntcodeOutput};
+use super::intcode_profiler::IntcodeProfile;
+use super::intcode_snapshot::IntcodeSnapshot;
+use super::intcode_tracer::{IntcodeTracer, TraceEntry};
+use crate::{Error, Result};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+use std::io::Write;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
-#[derive(Debug)]
 pub struct IntcodeComputer {
     pub memory: IntcodeProgram,
     instruction_pointer: usize,
     relative_base: i64,
-    input: Option<Receiver<i64>>,
-    output: Option<Sender<i64>>,
+    pending_input: VecDeque<i64>,
+    input: Option<Box<dyn IntcodeInput>>,
+    output: Option<Box<dyn IntcodeOutput>>,
+    tracer: Option<IntcodeTracer>,
+    memory_limit: Option<usize>,
+    instruction_limit: Option<u64>,
+    time_limit: Option<Duration>,
+    instructions_executed: u64,
+    decode_cache: bool,
+    profile: Option<IntcodeProfile>,
+    halted: bool,
+}
+
+/// How many instructions run between checks of the time limit, as reading the clock is slow compared to an instruction
+pub(super) const TIME_LIMIT_CHECK_INTERVAL: u64 = 1024;
+
+/// Why a running `IntcodeComputer` stopped and handed control back to its caller
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntcodeEvent {
+    /// The program wants input, but none is pending and the connected input (if any) has run out.
+    ///
+    /// The input instruction will be retried when the computer is resumed, so push some input first.
+    NeedsInput,
+
+    /// The program produced an output value, and no output is connected to write it to
+    Output(i64),
+
+    /// The program has halted, and will not run any further
+    Halted,
 }
 
 impl IntcodeComputer {
@@ -16,201 +55,588 @@ impl IntcodeComputer {
         self.memory = program.clone();
         self.instruction_pointer = 0;
         self.relative_base = 0;
+        self.pending_input.clear();
+        self.instructions_executed = 0;
+        self.halted = false;
+        if let Some(profile) = &mut self.profile {
+            *profile = IntcodeProfile::default();
+        }
+    }
+
+    pub fn instruction_pointer(&self) -> usize {
+        self.instruction_pointer
+    }
+
+    pub fn relative_base(&self) -> i64 {
+        self.relative_base
+    }
+
+    /// How many instructions the program has executed since it was loaded
+    pub fn instructions_executed(&self) -> u64 {
+        self.instructions_executed
+    }
+
+    /// Input values queued with `push_input` which the program hasn't read yet
+    pub fn pending_input(&self) -> &VecDeque<i64> {
+        &self.pending_input
     }
 
-    pub fn run_new_in_thread(program: IntcodeProgram) -> (Sender<i64>, Receiver<i64>) {
+    /// Saves the state of the program, so it can be restored later
+    pub fn snapshot(&self) -> IntcodeSnapshot {
+        IntcodeSnapshot {
+            memory: self.memory.data().clone(),
+            instruction_pointer: self.instruction_pointer,
+            relative_base: self.relative_base,
+            pending_input: self.pending_input.iter().copied().collect(),
+            sparse_memory: self.memory.sparse_data().collect(),
+        }
+    }
+
+    /// Restores the state of the program from a snapshot, keeping the connected input, output and tracer
+    pub fn restore(&mut self, snapshot: &IntcodeSnapshot) {
+        self.memory = IntcodeProgram::from(snapshot.memory.clone());
+        for &(address, integer) in &snapshot.sparse_memory {
+            self.memory.replace(address, integer);
+        }
+        self.instruction_pointer = snapshot.instruction_pointer;
+        self.relative_base = snapshot.relative_base;
+        self.pending_input = snapshot.pending_input.iter().copied().collect();
+        self.halted = false;
+    }
+
+    /// Queues a value for the program's next input instruction, ahead of any connected input
+    pub fn push_input(&mut self, value: i64) {
+        self.pending_input.push_back(value);
+    }
+
+    /// Runs a program in a new thread, connected to channels.
+    ///
+    /// Returns the sending half of its input, the receiving half of its output, and a handle to join the thread and
+    /// find out whether the program halted or failed.
+    pub fn run_new_in_thread(
+        program: IntcodeProgram,
+    ) -> (
+        Sender<i64>,
+        Receiver<i64>,
+        JoinHandle<Result<(), IntcodeError>>,
+    ) {
         let (input_tx, input_rx) = mpsc::channel();
         let (output_tx, output_rx) = mpsc::channel();
 
-        thread::spawn(move || {
-            let mut computer = IntcodeComputer::from(&program.clone());
-            computer.input = Some(input_rx);
-            computer.output = Some(output_tx);
+        let handle = thread::spawn(move || {
+            let mut computer = IntcodeComputer::from(&program);
+            computer.set_input(input_rx);
+            computer.set_output(output_tx);
 
-            computer.run();
+            computer.run()
         });
 
-        (input_tx, output_rx)
+        (input_tx, output_rx, handle)
     }
 
+    /// Connects the source the program reads its input from
+    pub fn set_input(&mut self, input: impl IntcodeInput + 'static) {
+        self.input = Some(Box::new(input));
+    }
+
+    /// Connects the destination the program writes its output to
+    pub fn set_output(&mut self, output: impl IntcodeOutput + 'static) {
+        self.output = Some(Box::new(output));
+    }
+
+    /// Limits how many values the program's memory may hold, or removes the limit.
+    ///
+    /// A write which would grow memory past the limit fails with `IntcodeErrorKind::MemoryLimitExceeded`, instead of
+    /// allocating. Values written through `memory` directly aren't limited.
+    pub fn set_memory_limit(&mut self, limit: Option<usize>) {
+        self.memory_limit = limit;
+    }
+
+    /// Limits how many instructions the program may execute after it's loaded, or removes the limit.
+    ///
+    /// Executing any more fails with `IntcodeErrorKind::InstructionLimitExceeded`.
+    pub fn set_instruction_limit(&mut self, limit: Option<u64>) {
+        self.instruction_limit = limit;
+    }
+
+    /// Limits how long each call to `run_until_event` (and so `run` and `run_with_input`) may take, or removes the
+    /// limit.
+    ///
+    /// Running for any longer fails with `IntcodeErrorKind::TimedOut`. Time spent waiting for a connected input counts
+    /// towards the limit, as long as the input implements `IntcodeInput::read_timeout` (as channels do).
+    pub fn set_time_limit(&mut self, limit: Option<Duration>) {
+        self.time_limit = limit;
+    }
+
+    /// Whether to reuse decoded instructions (the default), or decode every instruction every time it runs.
+    ///
+    /// Decoded instructions are cached per address, and dropped whenever any part of them is written to, so the cache
+    /// never changes what a program does; turning it off is only useful to measure how much it helps.
+    pub fn set_decode_cache(&mut self, enabled: bool) {
+        self.decode_cache = enabled;
+    }
+
+    /// Turns counting executed instructions on (starting from zero) or off
+    pub fn set_profiling(&mut self, enabled: bool) {
+        self.profile = if enabled {
+            Some(IntcodeProfile::default())
+        } else {
+            None
+        };
+    }
+
+    /// The counts of instructions executed since profiling was turned on or the program was loaded, if profiling is on
+    pub fn profile(&self) -> Option<&IntcodeProfile> {
+        self.profile.as_ref()
+    }
+
+    /// Records every instruction the program executes to `tracer`, in the line-oriented format described in
+    /// `intcode_tracer`
+    pub fn set_tracer(&mut self, tracer: impl Write + 'static) {
+        self.tracer = Some(IntcodeTracer::new(tracer));
+    }
+
+    /// Connects the input to a channel, returning its sending half
     pub fn create_input(&mut self) -> Sender<i64> {
         let (input_tx, input_rx) = mpsc::channel();
-        self.input = Some(input_rx);
+        self.set_input(input_rx);
         input_tx
     }
 
+    /// Connects the output to a channel, returning its receiving half
     pub fn create_output(&mut self) -> Receiver<i64> {
         let (output_tx, output_rx) = mpsc::channel();
-        self.output = Some(output_tx);
+        self.set_output(output_tx);
         output_rx
     }
 
-    pub fn run(&mut self) {
+    /// Runs the program until it halts, reading input from `input` and returning all of its output.
+    ///
+    /// Both `input` and the output are disconnected again afterwards, so any input the program didn't read is dropped.
+    pub fn run_with_input<I>(&mut self, input: I) -> Result<Vec<i64>, IntcodeError>
+    where
+        I: IntoIterator<Item = i64>,
+        I::IntoIter: 'static,
+    {
+        let output = Rc::new(RefCell::new(Vec::new()));
+
+        self.set_input(InputIter::new(input));
+        self.set_output(Rc::clone(&output));
+        let result = self.run();
+        self.input = None;
+        self.output = None;
+
+        result.map(|()| output.replace(Vec::new()))
+    }
+
+    /// Runs the program until it halts.
+    ///
+    /// Running out of input, or producing output with no output connected, are errors.
+    pub fn run(&mut self) -> Result<(), IntcodeError> {
+        match self.run_until_event()? {
+            IntcodeEvent::Halted => Ok(()),
+            IntcodeEvent::NeedsInput => Err(self.error(None, IntcodeErrorKind::InputUnavailable)),
+            IntcodeEvent::Output(value) => {
+                // the output instruction (which is two integers long) has already been executed
+                Err(IntcodeError::at(
+                    &self.memory,
+                    self.instruction_pointer - 2,
+                    None,
+                    IntcodeErrorKind::OutputNotConnected(value),
+                ))
+            }
+        }
+    }
+
+    /// Runs the program until it needs input, produces output (when no output is connected) or halts
+    pub fn run_until_event(&mut self) -> Result<IntcodeEvent, IntcodeError> {
+        let deadline = self.time_limit.map(|limit| (Instant::now() + limit, limit));
+
         loop {
-            let next_instruction = IntcodeInstruction::from(&*self);
-            let instruction_pointer_before_instruction = self.instruction_pointer;
-            let instruction_length = next_instruction.length();
-
-            match next_instruction {
-                IntcodeInstruction::Add(one, two, output) => {
-                    let one = one.get_value(&self);
-                    let two = two.get_value(&self);
-                    let output_address = output
-                        .get_address(&self)
-                        .expect("Add 'output' parameter must be an address");
-
-                    self.memory.replace(output_address, one + two)
+            if let Some((deadline, limit)) = deadline {
+                if self.instructions_executed % TIME_LIMIT_CHECK_INTERVAL == 0
+                    && Instant::now() > deadline
+                {
+                    return Err(self.error(None, IntcodeErrorKind::TimedOut(limit)));
                 }
+            }
 
-                IntcodeInstruction::Multiply(one, two, output) => {
-                    let one = one.get_value(&self);
-                    let two = two.get_value(&self);
-                    let output_address = output
-                        .get_address(&self)
-                        .expect("Multiply 'output' parameter must be an address");
+            if let Some(event) = self.step_until(deadline)? {
+                return Ok(event);
+            }
+        }
+    }
 
-                    self.memory.replace(output_address, one * two)
-                }
+    /// Executes a single instruction, returning the event it caused (if any)
+    pub fn step(&mut self) -> Result<Option<IntcodeEvent>, IntcodeError> {
+        self.step_until(None)
+    }
 
-                IntcodeInstruction::Input(to) => {
-                    let input_value = self
-                        .input
-                        .as_ref()
-                        .expect("Program requires input but no input was connected!")
-                        .recv()
-                        .expect("Failed to receive from input");
+    /// Executes a single instruction, giving up waiting for a connected input at `deadline` (with the time limit it
+    /// came from)
+    fn step_until(
+        &mut self,
+        deadline: Option<(Instant, Duration)>,
+    ) -> Result<Option<IntcodeEvent>, IntcodeError> {
+        if self.tracer.is_none() {
+            return self.execute(deadline);
+        }
 
-                    let to_address = to
-                        .get_address(&self)
-                        .expect("Input 'to' parameter must be an address");
+        let entry = TraceEntry::new(self)?;
+        let event = self.execute(deadline)?;
+        if event == Some(IntcodeEvent::NeedsInput) {
+            return Ok(event);
+        }
 
-                    self.memory.replace(to_address, input_value);
-                }
+        let line = entry.finish(self);
+        let tracer = self.tracer.as_mut().expect("tracer is connected");
+        tracer
+            .write_line(&line)
+            .and_then(|()| match event {
+                Some(_) => tracer.flush(),
+                None => Ok(()),
+            })
+            .map_err(|error| {
+                IntcodeError::at(
+                    &self.memory,
+                    entry.address(),
+                    None,
+                    IntcodeErrorKind::TraceFailed(error.to_string()),
+                )
+            })?;
+
+        Ok(event)
+    }
 
-                IntcodeInstruction::Output(from) => {
-                    let output_value = from.get_value(&self);
+    fn execute(
+        &mut self,
+        deadline: Option<(Instant, Duration)>,
+    ) -> Result<Option<IntcodeEvent>, IntcodeError> {
+        if let Some(limit) = self.instruction_limit {
+            if self.instructions_executed >= limit {
+                return Err(self.error(None, IntcodeErrorKind::InstructionLimitExceeded(limit)));
+            }
+        }
+
+        let mut event = None;
+
+        let next_instruction = if self.decode_cache {
+            self.memory.decode_cached(self.instruction_pointer)?
+        } else {
+            IntcodeInstruction::decode(&self.memory, self.instruction_pointer)?
+        };
+        let instruction_address = self.instruction_pointer;
+        let instruction_length = next_instruction.length();
+        let mut jumped = false;
+
+        match next_instruction {
+            IntcodeInstruction::Add(one, two, output) => {
+                let one = one.get_value(self)?;
+                let two = two.get_value(self)?;
+                let output_address = output.get_address(self)?;
+
+                let sum = one
+                    .checked_add(two)
+                    .ok_or_else(|| self.error(None, IntcodeErrorKind::Overflow))?;
+                self.write(output_address, sum)?
+            }
 
-                    self.output
-                        .as_ref()
-                        .expect("Program requires output but no output was connected!")
-                        .send(output_value)
-                        .expect("Failed to send to output");
+            IntcodeInstruction::Multiply(one, two, output) => {
+                let one = one.get_value(self)?;
+                let two = two.get_value(self)?;
+                let output_address = output.get_address(self)?;
+
+                let product = one
+                    .checked_mul(two)
+                    .ok_or_else(|| self.error(None, IntcodeErrorKind::Overflow))?;
+                self.write(output_address, product)?
+            }
+
+            IntcodeInstruction::Input(to) => {
+                // worked out first, so an invalid address doesn't use up any input
+                let to_address = to.get_address(self)?;
+
+                let input_value = match (self.pending_input.pop_front(), &mut self.input) {
+                    (Some(input_value), _) => input_value,
+                    (None, None) => return Ok(Some(IntcodeEvent::NeedsInput)),
+                    (None, Some(input)) => match deadline {
+                        None => match input.read() {
+                            Some(input_value) => input_value,
+                            None => return Ok(Some(IntcodeEvent::NeedsInput)),
+                        },
+                        Some((deadline, limit)) => {
+                            let remaining = deadline.saturating_duration_since(Instant::now());
+                            match input.read_timeout(remaining) {
+                                Ok(input_value) => input_value,
+                                Err(RecvTimeoutError::Disconnected) => {
+                                    return Ok(Some(IntcodeEvent::NeedsInput))
+                                }
+                                Err(RecvTimeoutError::Timeout) => {
+                                    return Err(self.error(None, IntcodeErrorKind::TimedOut(limit)))
+                                }
+                            }
+                        }
+                    },
+                };
+
+                if let Err(error) = self.write(to_address, input_value) {
+                    self.pending_input.push_front(input_value);
+                    return Err(error);
                 }
+            }
 
-                IntcodeInstruction::JumpIfTrue(test, jump_to) => {
-                    if test.get_value(&self) != 0 {
-                        self.instruction_pointer = jump_to.get_value(&self).try_into().unwrap();
+            IntcodeInstruction::Output(from) => {
+                let output_value = from.get_value(self)?;
+
+                match self.output.as_mut() {
+                    Some(output) => {
+                        if !output.write(output_value) {
+                            return Err(self
+                                .error(None, IntcodeErrorKind::OutputDisconnected(output_value)));
+                        }
                     }
+                    None => event = Some(IntcodeEvent::Output(output_value)),
                 }
+            }
 
-                IntcodeInstruction::JumpIfFalse(test, jump_to) => {
-                    if test.get_value(&self) == 0 {
-                        self.instruction_pointer = jump_to.get_value(&self).try_into().unwrap();
-                    }
+            IntcodeInstruction::JumpIfTrue(test, jump_to) => {
+                if test.get_value(self)? != 0 {
+                    self.jump(&jump_to)?;
+                    jumped = true;
                 }
+            }
 
-                IntcodeInstruction::LessThan(one, two, output) => {
-                    let one = one.get_value(&self);
-                    let two = two.get_value(&self);
+            IntcodeInstruction::JumpIfFalse(test, jump_to) => {
+                if test.get_value(self)? == 0 {
+                    self.jump(&jump_to)?;
+                    jumped = true;
+                }
+            }
 
-                    let output_value = if one < two { 1 } else { 0 };
+            IntcodeInstruction::LessThan(one, two, output) => {
+                let one = one.get_value(self)?;
+                let two = two.get_value(self)?;
 
-                    let output_address = output
-                        .get_address(&self)
-                        .expect("LessThan 'output' parameter must be an address");
+                let output_value = if one < two { 1 } else { 0 };
 
-                    self.memory.replace(output_address, output_value)
-                }
+                let output_address = output.get_address(self)?;
 
-                IntcodeInstruction::Equals(one, two, output) => {
-                    let one = one.get_value(&self);
-                    let two = two.get_value(&self);
+                self.write(output_address, output_value)?
+            }
 
-                    let output_value = if one == two { 1 } else { 0 };
+            IntcodeInstruction::Equals(one, two, output) => {
+                let one = one.get_value(self)?;
+                let two = two.get_value(self)?;
 
-                    let output_address = output
-                        .get_address(&self)
-                        .expect("LessThan 'output' parameter must be an address");
+                let output_value = if one == two { 1 } else { 0 };
 
-                    self.memory.replace(output_address, output_value)
-                }
+                let output_address = output.get_address(self)?;
 
-                IntcodeInstruction::RelativeBaseOffset(offset) => {
-                    let offset = offset.get_value(&self);
+                self.write(output_address, output_value)?
+            }
 
-                    self.relative_base = self.relative_base + offset;
-                }
+            IntcodeInstruction::RelativeBaseOffset(offset) => {
+                let offset = offset.get_value(self)?;
 
-                IntcodeInstruction::Halt => break,
+                self.relative_base = self
+                    .relative_base
+                    .checked_add(offset)
+                    .ok_or_else(|| self.error(None, IntcodeErrorKind::Overflow))?;
             }
 
-            if instruction_pointer_before_instruction == self.instruction_pointer {
-                self.instruction_pointer += instruction_length;
+            IntcodeInstruction::Halt => {
+                // running a halted program again only reports that it halted, so it's profiled once
+                if !self.halted {
+                    self.halted = true;
+                    self.record_profile(instruction_address, next_instruction.opcode());
+                }
+                return Ok(Some(IntcodeEvent::Halted));
             }
         }
+
+        // a jump to the instruction's own address jumps too, rather than moving on
+        if !jumped {
+            self.instruction_pointer += instruction_length;
+        }
+        self.instructions_executed += 1;
+        self.record_profile(instruction_address, next_instruction.opcode());
+
+        Ok(event)
+    }
+
+    fn record_profile(&mut self, address: usize, opcode: i64) {
+        if let Some(profile) = &mut self.profile {
+            profile.record(address, opcode);
+        }
+    }
+
+    /// Writes to memory on behalf of the current instruction, unless memory would grow past its limit
+    fn write(&mut self, address: usize, value: i64) -> Result<(), IntcodeError> {
+        if let Some(limit) = self.memory_limit {
+            if self.memory.size() + self.memory.growth(address) > limit {
+                return Err(self.error(None, IntcodeErrorKind::MemoryLimitExceeded(address)));
+            }
+        }
+
+        self.memory.replace(address, value);
+        Ok(())
+    }
+
+    fn jump(&mut self, jump_to: &IntcodeParameter) -> Result<(), IntcodeError> {
+        let target = jump_to.get_value(self)?;
+
+        self.instruction_pointer = target.try_into().map_err(|_| {
+            self.error(
+                Some(jump_to.raw()),
+                IntcodeErrorKind::NegativeAddress(target),
+            )
+        })?;
+
+        Ok(())
+    }
+
+    /// An error caused by the current instruction
+    fn error(&self, parameter: Option<i64>, kind: IntcodeErrorKind) -> IntcodeError {
+        IntcodeError::at(&self.memory, self.instruction_pointer, parameter, kind)
     }
 }
 
-impl From<&IntcodeProgram> for IntcodeComputer {
-    fn from(program: &IntcodeProgram) -> Self {
+impl fmt::Debug for IntcodeComputer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IntcodeComputer")
+            .field("memory", &self.memory)
+            .field("instruction_pointer", &self.instruction_pointer)
+            .field("relative_base", &self.relative_base)
+            .field("pending_input", &self.pending_input)
+            .field("input_connected", &self.input.is_some())
+            .field("output_connected", &self.output.is_some())
+            .field("tracing", &self.tracer.is_some())
+            .field("memory_limit", &self.memory_limit)
+            .field("instruction_limit", &self.instruction_limit)
+            .field("time_limit", &self.time_limit)
+            .field("instructions_executed", &self.instructions_executed)
+            .field("decode_cache", &self.decode_cache)
+            .field("profile", &self.profile)
+            .field("halted", &self.halted)
+            .finish()
+    }
+}
+
+/// Clones the state of the program, so the clone can run on independently.
+///
+/// Connected inputs, outputs and tracers can't be shared, so the clone starts with none connected. It keeps the memory
+/// (with its decoded instructions), the pending input, the memory, instruction and time limits, the count of
+/// instructions executed, the profile (if profiling), and whether the decode cache is used.
+impl Clone for IntcodeComputer {
+    fn clone(&self) -> Self {
         Self {
-            memory: program.clone(),
-            instruction_pointer: 0,
-            relative_base: 0,
+            memory: self.memory.clone(),
+            instruction_pointer: self.instruction_pointer,
+            relative_base: self.relative_base,
+            pending_input: self.pending_input.clone(),
             input: None,
             output: None,
+            tracer: None,
+            memory_limit: self.memory_limit,
+            instruction_limit: self.instruction_limit,
+            time_limit: self.time_limit,
+            instructions_executed: self.instructions_executed,
+            decode_cache: self.decode_cache,
+            profile: self.profile.clone(),
+            halted: self.halted,
         }
     }
 }
 
-impl From<&str> for IntcodeComputer {
-    fn from(string: &str) -> Self {
+impl From<&IntcodeSnapshot> for IntcodeComputer {
+    fn from(snapshot: &IntcodeSnapshot) -> Self {
+        let mut computer = Self::from(&IntcodeProgram::from(Vec::new()));
+        computer.restore(snapshot);
+        computer
+    }
+}
+
+impl From<&IntcodeProgram> for IntcodeComputer {
+    fn from(program: &IntcodeProgram) -> Self {
         Self {
-            memory: IntcodeProgram::from(string),
+            memory: program.clone(),
             instruction_pointer: 0,
             relative_base: 0,
+            pending_input: VecDeque::new(),
             input: None,
             output: None,
+            tracer: None,
+            memory_limit: None,
+            instruction_limit: None,
+            time_limit: None,
+            instructions_executed: 0,
+            decode_cache: true,
+            profile: None,
+            halted: false,
         }
     }
 }
 
-#[derive(Debug)]
-enum IntcodeInstruction {
+impl FromStr for IntcodeComputer {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        Ok(Self::from(&string.parse::<IntcodeProgram>()?))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(super) enum IntcodeInstruction<V = i64> {
     /// Adds the values from the first two parameters, writes the result to the third parameter
-    Add(IntcodeParameter, IntcodeParameter, IntcodeParameter),
+    Add(
+        IntcodeParameter<V>,
+        IntcodeParameter<V>,
+        IntcodeParameter<V>,
+    ),
 
     /// Multiplies the values from the first two parameters, writes the result to the third parameter
-    Multiply(IntcodeParameter, IntcodeParameter, IntcodeParameter),
+    Multiply(
+        IntcodeParameter<V>,
+        IntcodeParameter<V>,
+        IntcodeParameter<V>,
+    ),
 
     /// Takes a single integer from input and writes it to the first parameter
-    Input(IntcodeParameter),
+    Input(IntcodeParameter<V>),
 
     /// Sends a single integer to output from the first parameter
-    Output(IntcodeParameter),
+    Output(IntcodeParameter<V>),
 
     /// If the first parameter is non-zero, sets the instruction pointer to the value of the second parameter.
-    JumpIfTrue(IntcodeParameter, IntcodeParameter),
+    JumpIfTrue(IntcodeParameter<V>, IntcodeParameter<V>),
 
     /// If the first parameter is zero, sets the instruction pointer to the value of the second parameter.
-    JumpIfFalse(IntcodeParameter, IntcodeParameter),
+    JumpIfFalse(IntcodeParameter<V>, IntcodeParameter<V>),
 
     /// If the first parameter is less than the second parameter, writes 1 to the third parameter.
     /// Otherwise, writes 0 to the third parameter.
-    LessThan(IntcodeParameter, IntcodeParameter, IntcodeParameter),
+    LessThan(
+        IntcodeParameter<V>,
+        IntcodeParameter<V>,
+        IntcodeParameter<V>,
+    ),
 
     /// If the first parameter is equal to the second parameter, writes 1 to the third parameter.
     /// Otherwise, writes 0 to the third parameter.
-    Equals(IntcodeParameter, IntcodeParameter, IntcodeParameter),
+    Equals(
+        IntcodeParameter<V>,
+        IntcodeParameter<V>,
+        IntcodeParameter<V>,
+    ),
 
     /// Adjusts the relative base by the value of its only parameter.
-    RelativeBaseOffset(IntcodeParameter),
+    RelativeBaseOffset(IntcodeParameter<V>),
 
     /// Halts the IntcodeComputer
     Halt,
 }
 
-impl IntcodeInstruction {
+impl<V> IntcodeInstruction<V> {
     pub fn length(&self) -> usize {
         match self {
             Self::Add(..) => 4,
@@ -225,190 +651,369 @@ impl IntcodeInstruction {
             Self::Halt => 1,
         }
     }
+
+    pub fn opcode(&self) -> i64 {
+        match self {
+            Self::Add(..) => 1,
+            Self::Multiply(..) => 2,
+            Self::Input(..) => 3,
+            Self::Output(..) => 4,
+            Self::JumpIfTrue(..) => 5,
+            Self::JumpIfFalse(..) => 6,
+            Self::LessThan(..) => 7,
+            Self::Equals(..) => 8,
+            Self::RelativeBaseOffset(..) => 9,
+            Self::Halt => 99,
+        }
+    }
+
+    pub fn parameters(&self) -> Vec<&IntcodeParameter<V>> {
+        match self {
+            Self::Add(a, b, c)
+            | Self::Multiply(a, b, c)
+            | Self::LessThan(a, b, c)
+            | Self::Equals(a, b, c) => vec![a, b, c],
+            Self::JumpIfTrue(a, b) | Self::JumpIfFalse(a, b) => vec![a, b],
+            Self::Input(a) | Self::Output(a) | Self::RelativeBaseOffset(a) => vec![a],
+            Self::Halt => vec![],
+        }
+    }
+
+    /// The instruction header which encodes this instruction's opcode and parameter modes
+    pub fn header(&self) -> i64 {
+        self.parameters()
+            .iter()
+            .enumerate()
+            .map(|(i, parameter)| parameter.mode() * 10_i64.pow(2 + i as u32))
+            .sum::<i64>()
+            + self.opcode()
+    }
+}
+
+impl IntcodeInstruction {
+    /// Decodes the instruction at `address` in `memory`
+    pub(super) fn decode(memory: &IntcodeProgram, address: usize) -> Result<Self, IntcodeError> {
+        Self::decode_with(
+            address,
+            |address| memory.get(address),
+            |parameter, kind| IntcodeError::at(memory, address, parameter.copied(), kind),
+        )
+    }
 }
 
-impl From<&IntcodeComputer> for IntcodeInstruction {
-    fn from(state: &IntcodeComputer) -> Self {
-        let instruction_header = state.memory.get(state.instruction_pointer);
-        let opcode = Opcode::from(instruction_header);
-        let mut parser = ParameterParser::from(instruction_header);
+impl<V: IntcodeValue> IntcodeInstruction<V> {
+    /// Decodes the instruction at `address`, reading memory with `get` and reporting faults with `error`, which is
+    /// given the parameter at fault (if any)
+    pub(super) fn decode_with(
+        address: usize,
+        get: impl Fn(usize) -> V,
+        error: impl Fn(Option<&V>, IntcodeErrorKind) -> IntcodeError,
+    ) -> Result<Self, IntcodeError> {
+        let mut parser = ParameterParser {
+            instruction_header: get(address),
+            get,
+            error,
+            address,
+            parameters_read: 0,
+        };
 
-        match opcode {
+        let instruction = match Opcode::from(&parser.instruction_header) {
             Opcode(1) => Self::Add(
-                parser.parse_next(state.memory.get(state.instruction_pointer + 1)),
-                parser.parse_next(state.memory.get(state.instruction_pointer + 2)),
-                parser.parse_writeonly(state.memory.get(state.instruction_pointer + 3)),
+                parser.parse_next()?,
+                parser.parse_next()?,
+                parser.parse_writeonly()?,
             ),
             Opcode(2) => Self::Multiply(
-                parser.parse_next(state.memory.get(state.instruction_pointer + 1)),
-                parser.parse_next(state.memory.get(state.instruction_pointer + 2)),
-                parser.parse_writeonly(state.memory.get(state.instruction_pointer + 3)),
-            ),
-            Opcode(3) => {
-                Self::Input(parser.parse_writeonly(state.memory.get(state.instruction_pointer + 1)))
-            }
-            Opcode(4) => {
-                Self::Output(parser.parse_next(state.memory.get(state.instruction_pointer + 1)))
-            }
-            Opcode(5) => Self::JumpIfTrue(
-                parser.parse_next(state.memory.get(state.instruction_pointer + 1)),
-                parser.parse_next(state.memory.get(state.instruction_pointer + 2)),
-            ),
-            Opcode(6) => Self::JumpIfFalse(
-                parser.parse_next(state.memory.get(state.instruction_pointer + 1)),
-                parser.parse_next(state.memory.get(state.instruction_pointer + 2)),
+                parser.parse_next()?,
+                parser.parse_next()?,
+                parser.parse_writeonly()?,
             ),
+            Opcode(3) => Self::Input(parser.parse_writeonly()?),
+            Opcode(4) => Self::Output(parser.parse_next()?),
+            Opcode(5) => Self::JumpIfTrue(parser.parse_next()?, parser.parse_next()?),
+            Opcode(6) => Self::JumpIfFalse(parser.parse_next()?, parser.parse_next()?),
             Opcode(7) => Self::LessThan(
-                parser.parse_next(state.memory.get(state.instruction_pointer + 1)),
-                parser.parse_next(state.memory.get(state.instruction_pointer + 2)),
-                parser.parse_writeonly(state.memory.get(state.instruction_pointer + 3)),
+                parser.parse_next()?,
+                parser.parse_next()?,
+                parser.parse_writeonly()?,
             ),
             Opcode(8) => Self::Equals(
-                parser.parse_next(state.memory.get(state.instruction_pointer + 1)),
-                parser.parse_next(state.memory.get(state.instruction_pointer + 2)),
-                parser.parse_writeonly(state.memory.get(state.instruction_pointer + 3)),
-            ),
-            Opcode(9) => Self::RelativeBaseOffset(
-                parser.parse_next(state.memory.get(state.instruction_pointer + 1)),
+                parser.parse_next()?,
+                parser.parse_next()?,
+                parser.parse_writeonly()?,
             ),
+            Opcode(9) => Self::RelativeBaseOffset(parser.parse_next()?),
             Opcode(99) => Self::Halt,
-            Opcode(other) => panic!("Invalid Opcode encountered: {}", other),
-        }
+            Opcode(other) => {
+                return Err((parser.error)(None, IntcodeErrorKind::InvalidOpcode(other)))
+            }
+        };
+
+        Ok(instruction)
     }
 }
 
 #[derive(Debug)]
 struct Opcode(i64);
-impl From<i64> for Opcode {
-    fn from(instruction_header: i64) -> Self {
-        Self(get_digit(instruction_header, 1) * 10 + get_digit(instruction_header, 0))
+impl<V: IntcodeValue> From<&V> for Opcode {
+    fn from(instruction_header: &V) -> Self {
+        Self(instruction_header.digit(1) * 10 + instruction_header.digit(0))
     }
 }
 
-#[derive(Debug)]
-enum IntcodeParameter {
+#[derive(Debug, Clone, Copy)]
+pub(super) enum IntcodeParameter<V = i64> {
     /// PositionMode
     Position(usize),
 
     /// ImmediateMode
-    Value(i64),
+    Value(V),
 
     /// RelativeMode
-    Relative(i64),
+    Relative(V),
 }
 
-impl IntcodeParameter {
-    fn get_address(&self, computer: &IntcodeComputer) -> Option<usize> {
+impl<V> IntcodeParameter<V> {
+    /// The parameter mode digit of the parameter
+    pub fn mode(&self) -> i64 {
         match self {
-            Self::Position(address) => Some(*address),
-            Self::Value(_) => None,
-            Self::Relative(address) => Some((computer.relative_base + address).try_into().unwrap()),
+            Self::Position(_) => 0,
+            Self::Value(_) => 1,
+            Self::Relative(_) => 2,
         }
     }
+}
 
-    fn get_value(&self, computer: &IntcodeComputer) -> i64 {
+impl IntcodeParameter {
+    /// The parameter as it appears in memory
+    fn raw(&self) -> i64 {
         match self {
-            Self::Position(address) => computer.memory.get(*address),
+            Self::Position(address) => *address as i64,
             Self::Value(value) => *value,
-            Self::Relative(address) => computer
-                .memory
-                .get((computer.relative_base + address).try_into().unwrap()),
+            Self::Relative(offset) => *offset,
         }
     }
-}
 
-#[derive(Debug)]
-struct ParameterParser {
-    instruction_header: i64,
-    parameters_read: u32,
-}
+    pub fn get_address(&self, computer: &IntcodeComputer) -> Result<usize, IntcodeError> {
+        match self {
+            Self::Position(address) => Ok(*address),
+            Self::Value(_) => {
+                Err(computer.error(Some(self.raw()), IntcodeErrorKind::ImmediateModeWrite))
+            }
+            Self::Relative(offset) => {
+                let address = computer
+                    .relative_base
+                    .checked_add(*offset)
+                    .ok_or_else(|| computer.error(Some(*offset), IntcodeErrorKind::Overflow))?;
+                address.try_into().map_err(|_| {
+                    computer.error(Some(*offset), IntcodeErrorKind::NegativeAddress(address))
+                })
+            }
+        }
+    }
 
-impl From<i64> for ParameterParser {
-    fn from(instruction_header: i64) -> Self {
-        Self {
-            instruction_header,
-            parameters_read: 0,
+    pub fn get_value(&self, computer: &IntcodeComputer) -> Result<i64, IntcodeError> {
+        match self {
+            Self::Value(value) => Ok(*value),
+            _ => Ok(computer.memory.get(self.get_address(computer)?)),
         }
     }
 }
 
-impl ParameterParser {
-    fn parse_next(&mut self, parameter: i64) -> IntcodeParameter {
-        let mode = ParameterMode::from(&*self);
-        let parameter = match mode {
-            ParameterMode::PositionMode => {
-                IntcodeParameter::Position(parameter.try_into().unwrap())
+/// What decoding an instruction needs to know about the values an Intcode computer stores
+pub(super) trait IntcodeValue: Clone {
+    /// The digit at a zero-indexed position from the right (in base 10), negative if the value is
+    fn digit(&self, position: u32) -> i64;
+
+    /// The value as a memory address
+    fn to_address(&self) -> Result<usize, IntcodeErrorKind>;
+}
+
+impl IntcodeValue for i64 {
+    fn digit(&self, position: u32) -> i64 {
+        get_digit(*self, position)
+    }
+
+    fn to_address(&self) -> Result<usize, IntcodeErrorKind> {
+        usize::try_from(*self).map_err(|_| {
+            if *self < 0 {
+                IntcodeErrorKind::NegativeAddress(*self)
+            } else {
+                IntcodeErrorKind::Overflow
             }
-            ParameterMode::ImmediateMode => IntcodeParameter::Value(parameter),
-            ParameterMode::RelativeMode => IntcodeParameter::Relative(parameter),
-        };
+        })
+    }
+}
 
-        self.parameters_read += 1;
+/// Reads the parameters of the instruction at `address`, one by one
+struct ParameterParser<V, G, E> {
+    get: G,
+    error: E,
+    address: usize,
+    instruction_header: V,
+    parameters_read: u32,
+}
+
+impl<V, G, E> ParameterParser<V, G, E>
+where
+    V: IntcodeValue,
+    G: Fn(usize) -> V,
+    E: Fn(Option<&V>, IntcodeErrorKind) -> IntcodeError,
+{
+    fn parse_next(&mut self) -> Result<IntcodeParameter<V>, IntcodeError> {
+        self.parse(false)
+    }
 
-        parameter
+    fn parse_writeonly(&mut self) -> Result<IntcodeParameter<V>, IntcodeError> {
+        self.parse(true)
     }
 
-    fn parse_writeonly(&mut self, parameter: i64) -> IntcodeParameter {
-        let mode = ParameterMode::from(&*self);
-        let parameter = match mode {
-            ParameterMode::PositionMode => {
-                IntcodeParameter::Position(parameter.try_into().unwrap())
+    fn parse(&mut self, writeonly: bool) -> Result<IntcodeParameter<V>, IntcodeError> {
+        let parameter = match self.address.checked_add(1 + self.parameters_read as usize) {
+            Some(address) => (self.get)(address),
+            None => return Err((self.error)(None, IntcodeErrorKind::Overflow)),
+        };
+        let error = |kind| (self.error)(Some(&parameter), kind);
+
+        let mode = self.instruction_header.digit(2 + self.parameters_read);
+        let parameter = match ParameterMode::try_from(mode).map_err(error)? {
+            ParameterMode::Position => {
+                IntcodeParameter::Position(parameter.to_address().map_err(error)?)
+            }
+            ParameterMode::Immediate if writeonly => {
+                return Err(error(IntcodeErrorKind::ImmediateModeWrite))
             }
-            ParameterMode::ImmediateMode => panic!("ImmediateMode invalid for writeonly parameter"),
-            ParameterMode::RelativeMode => IntcodeParameter::Relative(parameter),
+            ParameterMode::Immediate => IntcodeParameter::Value(parameter),
+            ParameterMode::Relative => IntcodeParameter::Relative(parameter),
         };
 
         self.parameters_read += 1;
 
-        parameter
+        Ok(parameter)
     }
 }
 
 #[derive(Debug)]
 enum ParameterMode {
-    PositionMode,
-    ImmediateMode,
-    RelativeMode,
+    Position,
+    Immediate,
+    Relative,
 }
 
-impl From<&ParameterParser> for ParameterMode {
-    fn from(state: &ParameterParser) -> Self {
-        match get_digit(state.instruction_header, 2 + state.parameters_read) {
-            0 => Self::PositionMode,
-            1 => Self::ImmediateMode,
-            2 => Self::RelativeMode,
-            other => panic!("Invalid ParameterMode: {}", other),
+impl TryFrom<i64> for ParameterMode {
+    type Error = IntcodeErrorKind;
+
+    fn try_from(mode: i64) -> Result<Self, Self::Error> {
+        match mode {
+            0 => Ok(Self::Position),
+            1 => Ok(Self::Immediate),
+            2 => Ok(Self::Relative),
+            other => Err(IntcodeErrorKind::InvalidParameterMode(other)),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+/// Writes at least this far past the end of the dense region are stored sparsely, rather than growing it
+const DENSE_GROWTH_LIMIT: usize = 4096;
+
+/// An Intcode program, and the memory of a running one.
+///
+/// Memory is stored densely from address 0 up to the end of the program image (and anything written just past it),
+/// and sparsely for addresses further out, so a single write to a huge address doesn't allocate everything below it.
+/// Every address which hasn't been written to holds 0.
+///
+/// Instructions decoded from the dense region are cached until any of their values are written to.
+#[derive(Clone)]
 pub struct IntcodeProgram {
     data: Vec<i64>,
+    sparse: BTreeMap<usize, i64>,
+    decoded: Vec<Option<IntcodeInstruction>>,
 }
 
 impl IntcodeProgram {
     pub fn get(&self, address: usize) -> i64 {
-        *self.data.get(address).unwrap_or(&0)
+        match self.data.get(address) {
+            Some(integer) => *integer,
+            None => *self.sparse.get(&address).unwrap_or(&0),
+        }
     }
 
     pub fn replace(&mut self, address: usize, replacement: i64) {
-        if self.data.len() <= address {
+        // the longest instruction is 4 values, so only instructions starting up to 3 values earlier can include this one
+        for decoded in self
+            .decoded
+            .iter_mut()
+            .take(address + 1)
+            .skip(address.saturating_sub(3))
+        {
+            *decoded = None;
+        }
+
+        if let Some(integer) = self.data.get_mut(address) {
+            *integer = replacement;
+        } else if address - self.data.len() < DENSE_GROWTH_LIMIT {
             self.data.resize(address + 1, 0);
+            self.data[address] = replacement;
+
+            // anything stored sparsely which is now in the dense region moves into it
+            let moved = self.sparse.split_off(&self.data.len());
+            for (address, integer) in std::mem::replace(&mut self.sparse, moved) {
+                self.data[address] = integer;
+            }
+        } else {
+            self.sparse.insert(address, replacement);
+        }
+    }
+
+    /// Decodes the instruction at `address`, or reuses it if it was decoded before and hasn't been written to since
+    pub(super) fn decode_cached(
+        &mut self,
+        address: usize,
+    ) -> Result<IntcodeInstruction, IntcodeError> {
+        if let Some(Some(instruction)) = self.decoded.get(address) {
+            return Ok(*instruction);
         }
 
-        let integer = self
-            .data
-            .get_mut(address)
-            .unwrap_or_else(|| panic!("Failed to get_mut data at address {}", address));
+        let instruction = IntcodeInstruction::decode(self, address)?;
+        if address < self.data.len() {
+            if self.decoded.len() < self.data.len() {
+                self.decoded.resize(self.data.len(), None);
+            }
+            self.decoded[address] = Some(instruction);
+        }
+
+        Ok(instruction)
+    }
+
+    /// How many more values memory would hold after writing to `address`
+    pub fn growth(&self, address: usize) -> usize {
+        if address < self.data.len() || self.sparse.contains_key(&address) {
+            0
+        } else if address - self.data.len() < DENSE_GROWTH_LIMIT {
+            address + 1 - self.data.len()
+        } else {
+            1
+        }
+    }
 
-        *integer = replacement;
+    /// How many values memory holds, densely or sparsely
+    pub fn size(&self) -> usize {
+        self.data.len() + self.sparse.len()
     }
 
+    /// The dense region of memory: the program image, and anything written near it
     pub fn data(&self) -> &Vec<i64> {
         &self.data
     }
 
+    /// The addresses and values stored sparsely, beyond the dense region
+    pub fn sparse_data(&self) -> impl Iterator<Item = (usize, i64)> + '_ {
+        self.sparse
+            .iter()
+            .map(|(address, integer)| (*address, *integer))
+    }
+
+    /// The dense region of memory, in the comma-separated format programs are written in
     pub fn data_serialized(&self) -> String {
         self.data
             .iter()
@@ -418,20 +1023,327 @@ impl IntcodeProgram {
     }
 }
 
-impl From<&str> for IntcodeProgram {
-    fn from(string: &str) -> Self {
+impl From<Vec<i64>> for IntcodeProgram {
+    fn from(data: Vec<i64>) -> Self {
         Self {
-            data: string
-                .trim()
-                .split(',')
-                .map(|integer| integer.parse::<i64>())
-                .map(|parse_result| parse_result.expect("Failed to parse Intcode integer as i64"))
-                .collect(),
+            data,
+            sparse: BTreeMap::new(),
+            decoded: Vec::new(),
         }
     }
 }
 
+impl PartialEq for IntcodeProgram {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data && self.sparse == other.sparse
+    }
+}
+
+impl Eq for IntcodeProgram {}
+
+impl fmt::Debug for IntcodeProgram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IntcodeProgram")
+            .field("data", &self.data)
+            .field("sparse", &self.sparse)
+            .finish()
+    }
+}
+
+impl FromStr for IntcodeProgram {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        let data = string
+            .trim()
+            .split(',')
+            .map(str::trim)
+            .map(|integer| {
+                integer.parse::<i64>().map_err(|_| {
+                    Error::parse(
+                        string,
+                        integer,
+                        format!("invalid Intcode integer {:?}", integer),
+                    )
+                })
+            })
+            .collect::<Result<Vec<i64>>>()?;
+
+        Ok(Self::from(data))
+    }
+}
+
 /// Gets the digit from number at a zero-indexed position from the right (in base 10)
 fn get_digit(number: i64, position: u32) -> i64 {
     (number / (10_i64.pow(position))) % 10
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::year_2019::assemble;
+
+    #[test]
+    fn test_run_until_event() {
+        // doubles each input until it reads a zero
+        let mut computer: IntcodeComputer = "3,20,1006,20,14,102,2,20,20,4,20,1105,1,0,99"
+            .parse()
+            .unwrap();
+
+        assert_eq!(computer.run_until_event(), Ok(IntcodeEvent::NeedsInput));
+        assert_eq!(computer.instruction_pointer(), 0);
+
+        computer.push_input(21);
+        assert_eq!(computer.run_until_event(), Ok(IntcodeEvent::Output(42)));
+        assert_eq!(computer.run_until_event(), Ok(IntcodeEvent::NeedsInput));
+
+        computer.push_input(0);
+        assert_eq!(computer.step(), Ok(None));
+        assert_eq!(computer.run_until_event(), Ok(IntcodeEvent::Halted));
+        assert_eq!(computer.run_until_event(), Ok(IntcodeEvent::Halted));
+
+        // the simplest infinite loop jumps to itself
+        let mut computer: IntcodeComputer = "1105,1,0,99".parse().unwrap();
+        for _ in 0..3 {
+            assert_eq!(computer.step(), Ok(None));
+            assert_eq!(computer.instruction_pointer(), 0);
+        }
+    }
+
+    #[test]
+    fn test_run_with_input() {
+        // reads one input and outputs it
+        let program: IntcodeProgram = "3,0,4,0,99".parse().unwrap();
+        let mut computer = IntcodeComputer::from(&program);
+
+        assert_eq!(computer.run_with_input(vec![1, 2]), Ok(vec![1]));
+
+        // the input left over from the last run isn't read again
+        computer.load(&program);
+        assert_eq!(computer.run_until_event(), Ok(IntcodeEvent::NeedsInput));
+    }
+
+    #[test]
+    fn test_input_kept_on_error() {
+        let mut computer: IntcodeComputer = "3,-1,99".parse().unwrap();
+        let input_tx = computer.create_input();
+        input_tx.send(7).unwrap();
+
+        assert_eq!(
+            computer.run().unwrap_err().kind,
+            IntcodeErrorKind::NegativeAddress(-1)
+        );
+
+        // reads one input and outputs it
+        computer.load(&"3,0,4,0,99".parse().unwrap());
+        assert_eq!(computer.run_until_event(), Ok(IntcodeEvent::Output(7)));
+    }
+
+    #[test]
+    fn test_run_errors() {
+        let error = |program: &str| {
+            program
+                .parse::<IntcodeComputer>()
+                .unwrap()
+                .run()
+                .unwrap_err()
+        };
+
+        assert_eq!(
+            error("1,0,0,0,42"),
+            IntcodeError {
+                instruction_pointer: 4,
+                instruction_header: 42,
+                parameter: None,
+                kind: IntcodeErrorKind::InvalidOpcode(42),
+            }
+        );
+        assert_eq!(
+            error("11101,1,2,3,99"),
+            IntcodeError {
+                instruction_pointer: 0,
+                instruction_header: 11101,
+                parameter: Some(3),
+                kind: IntcodeErrorKind::ImmediateModeWrite,
+            }
+        );
+        assert_eq!(
+            error("1,-1,0,0,99").kind,
+            IntcodeErrorKind::NegativeAddress(-1)
+        );
+        assert_eq!(
+            error("109,-5,204,2,99").kind,
+            IntcodeErrorKind::NegativeAddress(-3)
+        );
+        assert_eq!(
+            error("1105,1,-7").kind,
+            IntcodeErrorKind::NegativeAddress(-7)
+        );
+        assert_eq!(
+            error("301,0,0,0,99").kind,
+            IntcodeErrorKind::InvalidParameterMode(3)
+        );
+        assert_eq!(error("3,0,99").kind, IntcodeErrorKind::InputUnavailable);
+        assert_eq!(
+            error("1101,9223372036854775807,1,0,99"),
+            IntcodeError {
+                instruction_pointer: 0,
+                instruction_header: 1101,
+                parameter: None,
+                kind: IntcodeErrorKind::Overflow,
+            }
+        );
+        assert_eq!(
+            error("1102,4611686018427387904,-3,0,99").kind,
+            IntcodeErrorKind::Overflow
+        );
+        assert_eq!(
+            error("109,9223372036854775807,204,1,99").kind,
+            IntcodeErrorKind::Overflow
+        );
+        assert_eq!(
+            error("104,7,99"),
+            IntcodeError {
+                instruction_pointer: 0,
+                instruction_header: 104,
+                parameter: None,
+                kind: IntcodeErrorKind::OutputNotConnected(7),
+            }
+        );
+
+        let mut computer: IntcodeComputer = "104,7,99".parse().unwrap();
+        drop(computer.create_output());
+        assert_eq!(
+            computer.run().unwrap_err().kind,
+            IntcodeErrorKind::OutputDisconnected(7)
+        );
+    }
+
+    #[test]
+    fn test_limits() {
+        let mut computer: IntcodeComputer = "1105,1,0".parse().unwrap();
+        computer.set_instruction_limit(Some(1000));
+        assert_eq!(
+            computer.run().unwrap_err(),
+            IntcodeError {
+                instruction_pointer: 0,
+                instruction_header: 1105,
+                parameter: None,
+                kind: IntcodeErrorKind::InstructionLimitExceeded(1000),
+            }
+        );
+        assert_eq!(computer.instructions_executed(), 1000);
+
+        let mut computer: IntcodeComputer = "1105,1,0".parse().unwrap();
+        computer.set_time_limit(Some(Duration::from_millis(10)));
+        assert_eq!(
+            computer.run().unwrap_err().kind,
+            IntcodeErrorKind::TimedOut(Duration::from_millis(10))
+        );
+
+        // waiting for a channel which never sends anything counts towards the time limit
+        let (_sender, receiver) = mpsc::channel();
+        let mut computer: IntcodeComputer = "3,0,99".parse().unwrap();
+        computer.set_input(receiver);
+        computer.set_time_limit(Some(Duration::from_millis(10)));
+        assert_eq!(
+            computer.run().unwrap_err().kind,
+            IntcodeErrorKind::TimedOut(Duration::from_millis(10))
+        );
+
+        // the limits apply across loading programs, and counting starts again for each one
+        let mut computer: IntcodeComputer = "1101,1,2,0,99".parse().unwrap();
+        computer.set_instruction_limit(Some(2));
+        computer.run().unwrap();
+        computer.load(&"1101,1,2,0,99".parse().unwrap());
+        computer.run().unwrap();
+        assert_eq!(computer.instructions_executed(), 1);
+    }
+
+    #[test]
+    fn test_decode_cache() {
+        // rewrites the parameter of its own output instruction after running it once
+        let program = assemble(
+            "start: OUT #1
+                    JNZ [done], #end
+                    ADD #0, #1 -> [done]
+                    ADD #0, #2 -> [start+1]
+                    JNZ #1, #start
+             end:   HALT
+             done:  DATA 0",
+        )
+        .unwrap();
+
+        for &decode_cache in &[false, true] {
+            let mut computer = IntcodeComputer::from(&program);
+            computer.set_decode_cache(decode_cache);
+            assert_eq!(computer.run_with_input(vec![]).unwrap(), vec![1, 2]);
+
+            // writes from outside the program invalidate cached instructions too
+            computer.load(&program);
+            assert_eq!(computer.run_until_event(), Ok(IntcodeEvent::Output(1)));
+            assert_eq!(computer.run_until_event(), Ok(IntcodeEvent::Output(2)));
+            computer.memory.replace(4, 0);
+            assert_eq!(computer.run_until_event(), Ok(IntcodeEvent::Output(2)));
+        }
+    }
+
+    #[test]
+    fn test_sparse_memory() {
+        let program = "1101,7,8,1000000000000,4,1000000000000,99";
+
+        let mut computer: IntcodeComputer = program.parse().unwrap();
+        computer.set_memory_limit(Some(8));
+        assert_eq!(computer.run_with_input(vec![]).unwrap(), vec![15]);
+        assert_eq!(computer.memory.size(), 8);
+        assert_eq!(computer.clone().memory.get(1_000_000_000_000), 15);
+
+        let mut computer: IntcodeComputer = "1101,1,2,20,99".parse().unwrap();
+        computer.set_memory_limit(Some(10));
+        assert_eq!(
+            computer.run().unwrap_err(),
+            IntcodeError {
+                instruction_pointer: 0,
+                instruction_header: 1101,
+                parameter: None,
+                kind: IntcodeErrorKind::MemoryLimitExceeded(20),
+            }
+        );
+
+        let mut memory: IntcodeProgram = "99".parse().unwrap();
+        memory.replace(5000, 1);
+        memory.replace(4000, 2);
+        assert_eq!(memory.sparse_data().collect::<Vec<_>>(), vec![(5000, 1)]);
+        assert_eq!(memory.growth(5100), 1100);
+
+        memory.replace(5100, 3);
+        assert_eq!(memory.sparse_data().count(), 0);
+        assert_eq!(memory.size(), 5101);
+        assert_eq!(
+            (memory.get(4000), memory.get(5000), memory.get(5100)),
+            (2, 1, 3)
+        );
+    }
+
+    #[test]
+    fn test_clone() {
+        // outputs its input plus 1, then loops forever
+        let mut computer: IntcodeComputer = "3,9,1001,9,1,9,4,9,1105,1,8".parse().unwrap();
+        computer.set_instruction_limit(Some(10));
+        computer.set_profiling(true);
+        computer.push_input(1);
+        computer.push_input(2);
+        assert_eq!(computer.run_until_event(), Ok(IntcodeEvent::Output(2)));
+
+        let mut clone = computer.clone();
+        assert_eq!(clone.instruction_pointer(), 8);
+        assert_eq!(clone.pending_input(), &VecDeque::from(vec![2]));
+        assert_eq!(clone.instructions_executed(), 3);
+        assert_eq!(clone.profile(), computer.profile());
+        assert_eq!(
+            clone.run_until_event().unwrap_err().kind,
+            IntcodeErrorKind::InstructionLimitExceeded(10)
+        );
+        assert_eq!(computer.instructions_executed(), 3);
+    }
+}
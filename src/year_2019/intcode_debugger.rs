@@ -0,0 +1,348 @@
+//! An interactive debugger for Intcode programs.
+//!
+//! The debugger reads one command per line and prints what happened. Output values are printed as the program
+//! produces them, and the program pauses whenever it needs input until some is given with `input`. Enter `help`
+//! for a list of the commands.
+use super::intcode_disassembler::disassemble_at;
+use super::{IntcodeComputer, IntcodeEvent};
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, Write};
+
+pub struct IntcodeDebugger {
+    computer: IntcodeComputer,
+    breakpoints: BTreeSet<usize>,
+    previous_command: String,
+}
+
+impl IntcodeDebugger {
+    pub fn new(computer: IntcodeComputer) -> Self {
+        Self {
+            computer,
+            breakpoints: BTreeSet::new(),
+            previous_command: String::new(),
+        }
+    }
+
+    pub fn computer(&self) -> &IntcodeComputer {
+        &self.computer
+    }
+
+    /// Reads commands from `input` until it runs out or `quit` is entered, printing a prompt and each response to
+    /// `output`
+    pub fn run(&mut self, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        write!(output, "{}(intcode) ", self.location())?;
+        output.flush()?;
+
+        for line in input.lines() {
+            match self.execute(&line?) {
+                Some(response) => write!(output, "{}(intcode) ", response)?,
+                None => break,
+            }
+            output.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Executes a single command, returning what to print, or `None` if the debugger should quit
+    pub fn execute(&mut self, line: &str) -> Option<String> {
+        let line = match line.trim() {
+            "" => self.previous_command.clone(),
+            line => line.to_string(),
+        };
+        self.previous_command = line.clone();
+
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or_default();
+        let arguments: Vec<&str> = words.collect();
+
+        let response = match (command, arguments.as_slice()) {
+            ("", _) => Ok(String::new()),
+            ("q" | "quit", _) => return None,
+            ("s" | "step", []) => Ok(self.step(1)),
+            ("s" | "step", [count]) => parse(count).map(|count| self.step(count)),
+            ("c" | "continue", []) => Ok(self.resume()),
+            ("b" | "break", []) => Ok(self.list_breakpoints()),
+            ("b" | "break", [address]) => parse(address).map(|address| {
+                self.breakpoints.insert(address);
+                format!("breakpoint set at {}\n", address)
+            }),
+            ("d" | "delete", [address]) => parse(address).map(|address| {
+                if self.breakpoints.remove(&address) {
+                    format!("breakpoint at {} deleted\n", address)
+                } else {
+                    format!("no breakpoint at {}\n", address)
+                }
+            }),
+            ("i" | "input", values) if !values.is_empty() => values
+                .iter()
+                .map(|value| parse(value))
+                .collect::<Result<Vec<i64>, _>>()
+                .map(|values| {
+                    for value in values {
+                        self.computer.push_input(value);
+                    }
+                    self.info()
+                }),
+            ("m" | "mem", [address]) => parse(address).and_then(|address| self.memory(address, 1)),
+            ("m" | "mem", [address, count]) => parse(address)
+                .and_then(|address| Ok((address, parse(count)?)))
+                .and_then(|(address, count)| self.memory(address, count)),
+            ("set", [address, values @ ..]) if !values.is_empty() => parse(address)
+                .and_then(|address| {
+                    let values = values.iter().map(|value| parse(value));
+                    Ok((address, values.collect::<Result<Vec<i64>, _>>()?))
+                })
+                .and_then(|(address, values)| {
+                    end_address(address, values.len())?;
+                    for (i, value) in values.iter().enumerate() {
+                        self.computer.memory.replace(address + i, *value);
+                    }
+                    self.memory(address, values.len().min(MAX_COUNT))
+                }),
+            ("dis", []) => self.disassemble(self.computer.instruction_pointer(), 10),
+            ("dis", [address]) => parse(address).and_then(|address| self.disassemble(address, 10)),
+            ("dis", [address, count]) => parse(address)
+                .and_then(|address| Ok((address, parse(count)?)))
+                .and_then(|(address, count)| self.disassemble(address, count)),
+            ("info", []) => Ok(self.info()),
+            ("h" | "help", _) => Ok(HELP.to_string()),
+            _ => Err(format!("unknown command {:?}, try help", line)),
+        };
+
+        Some(response.unwrap_or_else(|error| format!("{}\n", error)))
+    }
+
+    /// Executes up to `count` instructions, stopping early if the program needs input, halts or fails
+    fn step(&mut self, count: usize) -> String {
+        let mut response = String::new();
+        for _ in 0..count {
+            if !self.step_once(&mut response) {
+                break;
+            }
+        }
+        response + &self.location()
+    }
+
+    /// Runs until the program reaches a breakpoint, needs input, halts or fails
+    fn resume(&mut self) -> String {
+        let mut response = String::new();
+        while self.step_once(&mut response) {
+            if self.at_breakpoint() {
+                response.push_str("breakpoint\n");
+                break;
+            }
+        }
+        response + &self.location()
+    }
+
+    /// Executes a single instruction and describes any event, returning whether the program can keep running
+    fn step_once(&mut self, response: &mut String) -> bool {
+        match self.computer.step() {
+            Ok(None) => true,
+            Ok(Some(IntcodeEvent::Output(value))) => {
+                writeln!(response, "output {}", value).unwrap();
+                true
+            }
+            Ok(Some(IntcodeEvent::NeedsInput)) => {
+                response.push_str("waiting for input\n");
+                false
+            }
+            Ok(Some(IntcodeEvent::Halted)) => {
+                response.push_str("halted\n");
+                false
+            }
+            Err(error) => {
+                writeln!(response, "error: {}", error).unwrap();
+                false
+            }
+        }
+    }
+
+    fn at_breakpoint(&self) -> bool {
+        self.breakpoints
+            .contains(&self.computer.instruction_pointer())
+    }
+
+    /// The instruction the program will execute next
+    fn location(&self) -> String {
+        format!(
+            "{}{}\n",
+            if self.at_breakpoint() { "*" } else { " " },
+            disassemble_at(&self.computer.memory, self.computer.instruction_pointer())
+        )
+    }
+
+    fn list_breakpoints(&self) -> String {
+        if self.breakpoints.is_empty() {
+            return "no breakpoints\n".to_string();
+        }
+
+        self.breakpoints
+            .iter()
+            .map(|address| format!("{}\n", disassemble_at(&self.computer.memory, *address)))
+            .collect()
+    }
+
+    fn memory(&self, address: usize, count: usize) -> Result<String, String> {
+        check_count(count)?;
+
+        Ok((address..end_address(address, count)?)
+            .map(|address| format!("{:04}: {}\n", address, self.computer.memory.get(address)))
+            .collect())
+    }
+
+    fn disassemble(&self, mut address: usize, count: usize) -> Result<String, String> {
+        check_count(count)?;
+
+        let mut response = String::new();
+        for _ in 0..count {
+            let line = disassemble_at(&self.computer.memory, address);
+            let marker = match address {
+                _ if address == self.computer.instruction_pointer() => ">",
+                _ if self.breakpoints.contains(&address) => "*",
+                _ => " ",
+            };
+            writeln!(response, "{}{}", marker, line).unwrap();
+            address = match address.checked_add(line.length) {
+                Some(next) => next,
+                None => break,
+            };
+        }
+        Ok(response)
+    }
+
+    fn info(&self) -> String {
+        format!(
+            "instruction pointer {}, relative base {}, queued input {:?}\n",
+            self.computer.instruction_pointer(),
+            self.computer.relative_base(),
+            self.computer.pending_input()
+        )
+    }
+}
+
+/// The most values `mem` prints, or instructions `dis` disassembles, at once
+const MAX_COUNT: usize = 1000;
+
+/// The debugger's commands, as printed by `help`
+const HELP: &str = "\
+step [n]                  execute the next n instructions (default 1)
+continue                  run until a breakpoint, input is needed, the program halts or fails
+break [address]           set a breakpoint, or list the breakpoints
+delete <address>          remove a breakpoint
+input <value>...          queue input values
+mem <address> [count]     print count values of memory (default 1)
+set <address> <value>...  overwrite memory starting at address
+dis [address] [count]     disassemble count instructions (default 10) from address (default the next one)
+info                      print the instruction pointer, relative base and queued input
+help
+quit
+
+Every command except set, dis and info can be abbreviated to its first letter, and an empty line repeats the
+previous command.
+";
+
+fn check_count(count: usize) -> Result<(), String> {
+    if count > MAX_COUNT {
+        return Err(format!(
+            "count {} is more than the maximum of {}",
+            count, MAX_COUNT
+        ));
+    }
+    Ok(())
+}
+
+/// The address after `count` values starting at `address`
+fn end_address(address: usize, count: usize) -> Result<usize, String> {
+    address
+        .checked_add(count)
+        .ok_or_else(|| format!("address {} plus {} is out of range", address, count))
+}
+
+fn parse<T: std::str::FromStr>(argument: &str) -> Result<T, String> {
+    argument
+        .parse()
+        .map_err(|_| format!("invalid number {:?}", argument))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debugger() {
+        // doubles each input until it reads a zero
+        let computer: IntcodeComputer = "3,20,1006,20,14,102,2,20,20,4,20,1105,1,0,99"
+            .parse()
+            .unwrap();
+        let mut debugger = IntcodeDebugger::new(computer);
+
+        assert_eq!(
+            debugger.execute("continue").unwrap(),
+            "waiting for input\n 0000: IN -> [20]\n"
+        );
+        assert_eq!(
+            debugger.execute("input 21 0").unwrap(),
+            "instruction pointer 0, relative base 0, queued input [21, 0]\n"
+        );
+        assert_eq!(debugger.execute("s").unwrap(), " 0002: JZ [20], #14\n");
+        assert_eq!(debugger.execute("mem 20").unwrap(), "0020: 21\n");
+        assert_eq!(
+            debugger.execute("break 9").unwrap(),
+            "breakpoint set at 9\n"
+        );
+        assert_eq!(
+            debugger.execute("c").unwrap(),
+            "breakpoint\n*0009: OUT [20]\n"
+        );
+        assert_eq!(debugger.execute("set 20 50").unwrap(), "0020: 50\n");
+        assert_eq!(
+            debugger.execute("c").unwrap(),
+            "output 50\nhalted\n 0014: HALT\n"
+        );
+        assert_eq!(debugger.execute("").unwrap(), "halted\n 0014: HALT\n");
+        assert_eq!(
+            debugger.execute("dis 9 2").unwrap(),
+            "*0009: OUT [20]\n 0011: JNZ #1, #0\n"
+        );
+        assert_eq!(debugger.execute("quit"), None);
+    }
+
+    #[test]
+    fn test_run() {
+        let computer: IntcodeComputer = "104,7,99".parse().unwrap();
+        let mut output = Vec::new();
+        IntcodeDebugger::new(computer)
+            .run("step\nbogus\nstep\nq\n".as_bytes(), &mut output)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            " 0000: OUT #7\n(intcode) output 7\n 0002: HALT\n(intcode) unknown command \"bogus\", try help\n(intcode) halted\n 0002: HALT\n(intcode) "
+        );
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let mut debugger = IntcodeDebugger::new("99".parse().unwrap());
+
+        assert_eq!(
+            debugger.execute("mem 0 1000000000000").unwrap(),
+            "count 1000000000000 is more than the maximum of 1000\n"
+        );
+        assert_eq!(
+            debugger.execute("mem 18446744073709551615 2").unwrap(),
+            "address 18446744073709551615 plus 2 is out of range\n"
+        );
+        assert_eq!(
+            debugger.execute("set 18446744073709551615 1 2").unwrap(),
+            "address 18446744073709551615 plus 2 is out of range\n"
+        );
+        assert_eq!(
+            debugger.execute("dis 18446744073709551615 2").unwrap(),
+            " 18446744073709551615: DATA 0\n"
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! Turns Intcode programs back into readable listings.
+//!
+//! Each instruction is printed on its own line, prefixed with its address:
+//!
+//! ```text
+//! 0000: ADD [4], #3 -> [4]
+//! 0004: JNZ [rb+2], #0
+//! 0007: HALT
+//! 0008: DATA 7, -1, 1234
+//! ```
+//!
+//! Parameters are written as `[address]` in position mode, `#value` in immediate mode and `[rb+offset]` in relative
+//! mode, and the parameter an instruction writes to comes after the `->`.
+//!
+//! Programs mix code and data freely, so the listing is a linear sweep: any value which doesn't decode as an
+//! instruction (an unknown opcode or parameter mode, unused mode digits, or an instruction which runs past the end of
+//! the program) is printed as `DATA`, with consecutive data values grouped onto one line.
+use super::intcode_computer::{IntcodeInstruction, IntcodeParameter};
+use super::IntcodeProgram;
+use std::fmt;
+
+/// The most data values printed on a single `DATA` line
+const DATA_PER_LINE: usize = 8;
+
+/// A line of a disassembly listing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisassembledLine {
+    /// The address of the first value covered by this line
+    pub address: usize,
+
+    /// How many values of the program this line covers
+    pub length: usize,
+
+    /// The instruction or data, without its address
+    pub text: String,
+}
+
+impl fmt::Display for DisassembledLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}: {}", self.address, self.text)
+    }
+}
+
+/// Disassembles the whole program into a listing, one instruction or run of data per line
+pub fn disassemble(program: &IntcodeProgram) -> String {
+    disassemble_lines(program)
+        .iter()
+        .map(|line| format!("{}\n", line))
+        .collect()
+}
+
+/// Disassembles the whole program, one line per instruction or run of data
+pub fn disassemble_lines(program: &IntcodeProgram) -> Vec<DisassembledLine> {
+    let mut lines: Vec<DisassembledLine> = Vec::new();
+    let mut address = 0;
+
+    while address < program.data().len() {
+        let line = disassemble_at(program, address);
+        address += line.length;
+
+        match lines.last_mut() {
+            Some(previous)
+                if is_data(previous) && is_data(&line) && previous.length < DATA_PER_LINE =>
+            {
+                previous.length += 1;
+                previous.text.push_str(&line.text.replacen("DATA", ",", 1));
+            }
+            _ => lines.push(line),
+        }
+    }
+
+    lines
+}
+
+/// Disassembles the single instruction at `address`, or a single `DATA` value if it doesn't decode
+pub fn disassemble_at(program: &IntcodeProgram, address: usize) -> DisassembledLine {
+    let instruction = IntcodeInstruction::decode(program, address)
+        .ok()
+        .filter(|instruction| instruction.header() == program.get(address))
+        .filter(|instruction| {
+            // instructions may lie entirely beyond the dense region, but not straddle its end
+            address >= program.data().len()
+                || address + instruction.length() <= program.data().len()
+        });
+
+    match instruction {
+        Some(instruction) => DisassembledLine {
+            address,
+            length: instruction.length(),
+            text: instruction_text(&instruction),
+        },
+        None => DisassembledLine {
+            address,
+            length: 1,
+            text: format!("DATA {}", program.get(address)),
+        },
+    }
+}
+
+/// The mnemonic for each opcode, how many parameters the instruction takes, and whether it writes to its last parameter
+pub(super) const MNEMONICS: [(i64, &str, usize, bool); 10] = [
+    (1, "ADD", 3, true),
+    (2, "MUL", 3, true),
+    (3, "IN", 1, true),
+    (4, "OUT", 1, false),
+    (5, "JNZ", 2, false),
+    (6, "JZ", 2, false),
+    (7, "LT", 3, true),
+    (8, "EQ", 3, true),
+    (9, "ARB", 1, false),
+    (99, "HALT", 0, false),
+];
+
+/// The instruction as it appears in a listing, without its address
+pub(super) fn instruction_text(instruction: &IntcodeInstruction) -> String {
+    let (_, mnemonic, _, writes) = MNEMONICS
+        .iter()
+        .find(|(opcode, ..)| *opcode == instruction.opcode())
+        .expect("every opcode has a mnemonic");
+
+    let mut parameters: Vec<String> = instruction
+        .parameters()
+        .into_iter()
+        .map(parameter_text)
+        .collect();
+    let destination = if *writes { parameters.pop() } else { None };
+
+    let mut text = mnemonic.to_string();
+    if !parameters.is_empty() {
+        text.push(' ');
+        text.push_str(&parameters.join(", "));
+    }
+    if let Some(destination) = destination {
+        text.push_str(" -> ");
+        text.push_str(&destination);
+    }
+    text
+}
+
+fn parameter_text(parameter: &IntcodeParameter) -> String {
+    match parameter {
+        IntcodeParameter::Position(address) => format!("[{}]", address),
+        IntcodeParameter::Value(value) => format!("#{}", value),
+        IntcodeParameter::Relative(0) => "[rb]".to_string(),
+        IntcodeParameter::Relative(offset) if *offset < 0 => format!("[rb{}]", offset),
+        IntcodeParameter::Relative(offset) => format!("[rb+{}]", offset),
+    }
+}
+
+fn is_data(line: &DisassembledLine) -> bool {
+    line.text.starts_with("DATA")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disassemble() {
+        let program: IntcodeProgram = "1001,4,3,4,3,7,1205,-3,0,204,0,99".parse().unwrap();
+
+        assert_eq!(
+            disassemble(&program),
+            "0000: ADD [4], #3 -> [4]\n\
+             0004: IN -> [7]\n\
+             0006: JNZ [rb-3], #0\n\
+             0009: OUT [rb]\n\
+             0011: HALT\n"
+        );
+    }
+
+    #[test]
+    fn test_disassemble_data() {
+        // unknown opcode, unused mode digit, bad parameter mode, immediate write, and a truncated instruction
+        let program: IntcodeProgram = "99,42,1099,301,0,0,0,11101,1,2,3,-5,9,10,11,12,1,2"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            disassemble(&program),
+            "0000: HALT\n\
+             0001: DATA 42, 1099, 301, 0, 0, 0, 11101, 1\n\
+             0009: DATA 2, 3, -5\n\
+             0012: ARB [10]\n\
+             0014: DATA 11, 12, 1, 2\n"
+        );
+    }
+}
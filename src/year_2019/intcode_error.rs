@@ -0,0 +1,120 @@
+use super::IntcodeProgram;
+use std::error;
+use std::fmt;
+use std::time::Duration;
+
+/// A fault which stopped an `IntcodeComputer`, along with the instruction that caused it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntcodeError {
+    /// The address of the faulting instruction
+    pub instruction_pointer: usize,
+
+    /// The raw instruction header (opcode and parameter modes) at the instruction pointer
+    pub instruction_header: i64,
+
+    /// The raw value of the parameter which caused the fault, if a parameter caused it
+    pub parameter: Option<i64>,
+
+    pub kind: IntcodeErrorKind,
+}
+
+/// What went wrong while running an Intcode program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntcodeErrorKind {
+    /// The instruction header doesn't contain a known opcode
+    InvalidOpcode(i64),
+
+    /// The instruction header contains a parameter mode other than 0, 1 or 2
+    InvalidParameterMode(i64),
+
+    /// A parameter which is written to uses immediate mode
+    ImmediateModeWrite,
+
+    /// A parameter refers to (or jumps to) a negative address
+    NegativeAddress(i64),
+
+    /// The program needs input, but none is pending and the connected input (if any) has run out
+    InputUnavailable,
+
+    /// The program produced this output value, but no output is connected
+    OutputNotConnected(i64),
+
+    /// The connected output refused this output value
+    OutputDisconnected(i64),
+
+    /// An addition or multiplication (or the relative base) doesn't fit in 64 bits. Programs which need larger values
+    /// can run on a `BigIntcodeComputer` instead.
+    Overflow,
+
+    /// Writing to this address would grow memory past its limit
+    MemoryLimitExceeded(usize),
+
+    /// The program has already executed as many instructions as it's allowed to
+    InstructionLimitExceeded(u64),
+
+    /// The program ran for longer than it's allowed to
+    TimedOut(Duration),
+
+    /// Writing the execution trace failed
+    TraceFailed(String),
+}
+
+impl IntcodeError {
+    /// An error caused by the instruction at `address` in `memory`
+    pub fn at(
+        memory: &IntcodeProgram,
+        address: usize,
+        parameter: Option<i64>,
+        kind: IntcodeErrorKind,
+    ) -> Self {
+        Self {
+            instruction_pointer: address,
+            instruction_header: memory.get(address),
+            parameter,
+            kind,
+        }
+    }
+}
+
+impl fmt::Display for IntcodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at address {} (instruction {}",
+            self.kind, self.instruction_pointer, self.instruction_header
+        )?;
+        if let Some(parameter) = self.parameter {
+            write!(f, ", parameter {}", parameter)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl fmt::Display for IntcodeErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidOpcode(opcode) => write!(f, "invalid opcode {}", opcode),
+            Self::InvalidParameterMode(mode) => write!(f, "invalid parameter mode {}", mode),
+            Self::ImmediateModeWrite => write!(f, "write to an immediate mode parameter"),
+            Self::NegativeAddress(address) => write!(f, "negative address {}", address),
+            Self::InputUnavailable => write!(f, "no input available"),
+            Self::OutputNotConnected(value) => {
+                write!(f, "no output connected to write {} to", value)
+            }
+            Self::OutputDisconnected(value) => {
+                write!(f, "output disconnected while writing {}", value)
+            }
+            Self::Overflow => write!(f, "arithmetic overflow"),
+            Self::MemoryLimitExceeded(address) => {
+                write!(f, "writing to address {} exceeds the memory limit", address)
+            }
+            Self::InstructionLimitExceeded(limit) => {
+                write!(f, "instruction limit of {} instructions reached", limit)
+            }
+            Self::TimedOut(limit) => write!(f, "time limit of {:?} reached", limit),
+            Self::TraceFailed(error) => write!(f, "writing trace failed: {}", error),
+        }
+    }
+}
+
+impl error::Error for IntcodeError {}
@@ -0,0 +1,168 @@
+//! Where an `IntcodeComputer` reads its input from and writes its output to.
+//!
+//! Input and output are separate traits, as a computer usually reads from one place and writes to another (the next
+//! amplifier in a chain, a network card, a terminal). Both are implemented for:
+//!
+//! - mpsc channels (`Receiver<i64>` and `Sender<i64>`), for computers running in their own thread
+//! - `VecDeque<i64>`, for queues of values
+//! - closures (`FnMut() -> Option<i64>` and `FnMut(i64) -> bool`)
+//! - iterators of input values, via `InputIter`
+//! - `Rc<RefCell<_>>` of any of the above, so a queue can be shared between a computer and the code driving it
+//!
+//! Everything except the channels runs synchronously, in the same thread as the code driving the computer.
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender};
+use std::time::Duration;
+
+/// A source of input values for an `IntcodeComputer`
+pub trait IntcodeInput {
+    /// Reads the next input value, or returns `None` if no more input is available
+    fn read(&mut self) -> Option<i64>;
+
+    /// Reads the next input value like `read`, but waits at most `timeout` for one to arrive.
+    ///
+    /// This is used when the computer has a time limit. Only inputs which block need to implement it; the default just
+    /// calls `read`, returning `RecvTimeoutError::Disconnected` if no more input is available.
+    fn read_timeout(&mut self, timeout: Duration) -> Result<i64, RecvTimeoutError> {
+        let _ = timeout;
+        self.read().ok_or(RecvTimeoutError::Disconnected)
+    }
+}
+
+/// A destination for the output values of an `IntcodeComputer`
+pub trait IntcodeOutput {
+    /// Writes an output value, returning `false` if the output can't accept any more values
+    fn write(&mut self, value: i64) -> bool;
+}
+
+impl IntcodeInput for Receiver<i64> {
+    /// Blocks until the sending half sends a value, or returns `None` once it has disconnected
+    fn read(&mut self) -> Option<i64> {
+        self.recv().ok()
+    }
+
+    fn read_timeout(&mut self, timeout: Duration) -> Result<i64, RecvTimeoutError> {
+        self.recv_timeout(timeout)
+    }
+}
+
+impl IntcodeOutput for Sender<i64> {
+    fn write(&mut self, value: i64) -> bool {
+        self.send(value).is_ok()
+    }
+}
+
+impl IntcodeInput for VecDeque<i64> {
+    fn read(&mut self) -> Option<i64> {
+        self.pop_front()
+    }
+}
+
+impl IntcodeOutput for VecDeque<i64> {
+    fn write(&mut self, value: i64) -> bool {
+        self.push_back(value);
+        true
+    }
+}
+
+impl IntcodeOutput for Vec<i64> {
+    fn write(&mut self, value: i64) -> bool {
+        self.push(value);
+        true
+    }
+}
+
+impl<F: FnMut() -> Option<i64>> IntcodeInput for F {
+    fn read(&mut self) -> Option<i64> {
+        self()
+    }
+}
+
+impl<F: FnMut(i64) -> bool> IntcodeOutput for F {
+    fn write(&mut self, value: i64) -> bool {
+        self(value)
+    }
+}
+
+impl<T: IntcodeInput> IntcodeInput for Rc<RefCell<T>> {
+    fn read(&mut self) -> Option<i64> {
+        self.borrow_mut().read()
+    }
+
+    fn read_timeout(&mut self, timeout: Duration) -> Result<i64, RecvTimeoutError> {
+        self.borrow_mut().read_timeout(timeout)
+    }
+}
+
+impl<T: IntcodeOutput> IntcodeOutput for Rc<RefCell<T>> {
+    fn write(&mut self, value: i64) -> bool {
+        self.borrow_mut().write(value)
+    }
+}
+
+/// Reads input values from an iterator
+#[derive(Debug, Clone)]
+pub struct InputIter<I>(I);
+
+impl<I: Iterator<Item = i64>> InputIter<I> {
+    pub fn new<T: IntoIterator<IntoIter = I>>(values: T) -> Self {
+        Self(values.into_iter())
+    }
+}
+
+impl<I: Iterator<Item = i64>> IntcodeInput for InputIter<I> {
+    fn read(&mut self) -> Option<i64> {
+        self.0.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::year_2019::IntcodeComputer;
+
+    /// Outputs the sum of each pair of inputs, until it reads a zero
+    const SUM_PAIRS: &str = "3,20,1005,20,6,99,3,21,1,20,21,22,4,22,1105,1,0";
+
+    #[test]
+    fn test_queue_io() {
+        let output = Rc::new(RefCell::new(VecDeque::new()));
+
+        let mut computer: IntcodeComputer = SUM_PAIRS.parse().unwrap();
+        computer.set_input(VecDeque::from(vec![1, 2, 30, 40, 0]));
+        computer.set_output(Rc::clone(&output));
+        computer.run().unwrap();
+
+        assert_eq!(*output.borrow(), vec![3, 70]);
+    }
+
+    #[test]
+    fn test_closure_and_iterator_io() {
+        let sums = Rc::new(RefCell::new(Vec::new()));
+        let mut countdown = 3;
+
+        let mut computer: IntcodeComputer = SUM_PAIRS.parse().unwrap();
+        computer.set_input(move || {
+            countdown -= 1;
+            Some(countdown)
+        });
+        computer.set_output({
+            let sums = Rc::clone(&sums);
+            move |value| {
+                sums.borrow_mut().push(value);
+                true
+            }
+        });
+        computer.run().unwrap();
+
+        assert_eq!(*sums.borrow(), vec![3]);
+
+        let mut computer: IntcodeComputer = SUM_PAIRS.parse().unwrap();
+        assert_eq!(
+            computer.run_with_input(vec![5, 6, 7, 8, 0]).unwrap(),
+            vec![11, 15]
+        );
+    }
+}
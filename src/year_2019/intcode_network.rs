@@ -0,0 +1,291 @@
+//! A network of Intcode computers, running round-robin on a single thread.
+//!
+//! Each computer is given its network address (0 to N - 1) as its first input. It sends a packet by outputting the
+//! destination address followed by the packet's values (two by default), and receives packets as input, with the
+//! values of each packet in order. When a computer reads input and nothing has been sent to it, it reads -1 instead,
+//! and its turn ends.
+//!
+//! Packets sent to addresses outside the network aren't delivered anywhere: they're handed back to the code running
+//! the network as a `NetworkEvent::Packet`, which can act on them (say, as a NAT would) with `send` or `broadcast`.
+//! The network is idle once a whole round passes in which every computer is waiting for input and no packets are
+//! sent, which is also reported as an event.
+use super::{IntcodeComputer, IntcodeError, IntcodeEvent, IntcodeProgram};
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+
+/// How many instructions a computer may execute before its turn ends, if it doesn't wait for input first
+const DEFAULT_TURN_LENGTH: usize = 1000;
+
+/// What a computer reads when nothing has been sent to it
+const NO_PACKET: i64 = -1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    /// The address of the computer which sent the packet
+    pub source: usize,
+    pub destination: i64,
+    pub values: Vec<i64>,
+}
+
+/// Why a running `IntcodeNetwork` stopped and handed control back to its caller
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkEvent {
+    /// A packet was sent to an address outside the network
+    Packet(Packet),
+
+    /// Every computer is waiting for input, and none were sent any during the last round
+    Idle,
+
+    /// Every computer has halted
+    Halted,
+}
+
+#[derive(Debug)]
+struct Node {
+    computer: IntcodeComputer,
+
+    /// Values output so far towards the next packet
+    output: Vec<i64>,
+
+    /// Whether the computer's last turn ended with it waiting for input
+    waiting: bool,
+
+    halted: bool,
+}
+
+#[derive(Debug)]
+pub struct IntcodeNetwork {
+    nodes: Vec<Node>,
+    packet_length: usize,
+    turn_length: usize,
+
+    /// The address of the computer whose turn is next
+    next: usize,
+
+    /// Whether any packets have been sent during the current round
+    active: bool,
+
+    events: VecDeque<NetworkEvent>,
+}
+
+impl IntcodeNetwork {
+    /// A network of `size` computers all running `program`
+    pub fn new(program: &IntcodeProgram, size: usize) -> Self {
+        let nodes = (0..size)
+            .map(|address| {
+                let mut computer = IntcodeComputer::from(program);
+                computer.push_input(address as i64);
+
+                Node {
+                    computer,
+                    output: Vec::new(),
+                    waiting: false,
+                    halted: false,
+                }
+            })
+            .collect();
+
+        Self {
+            nodes,
+            packet_length: 2,
+            turn_length: DEFAULT_TURN_LENGTH,
+            next: 0,
+            active: false,
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Sets how many values follow the destination address in each packet
+    pub fn set_packet_length(&mut self, length: usize) {
+        self.packet_length = length;
+    }
+
+    /// Sets how many instructions a computer may execute in one turn, if it doesn't wait for input first
+    pub fn set_turn_length(&mut self, instructions: usize) {
+        self.turn_length = instructions.max(1);
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn computer(&self, address: usize) -> &IntcodeComputer {
+        &self.nodes[address].computer
+    }
+
+    pub fn computer_mut(&mut self, address: usize) -> &mut IntcodeComputer {
+        &mut self.nodes[address].computer
+    }
+
+    /// Delivers a packet's values to the computer at `destination`
+    pub fn send(&mut self, destination: usize, values: &[i64]) {
+        let node = &mut self.nodes[destination];
+        for value in values {
+            node.computer.push_input(*value);
+        }
+        node.waiting = false;
+        self.active = true;
+    }
+
+    /// Delivers a packet's values to every computer
+    pub fn broadcast(&mut self, values: &[i64]) {
+        for destination in 0..self.nodes.len() {
+            self.send(destination, values);
+        }
+    }
+
+    /// Runs the computers in turn until a packet is sent outside the network, the network is idle or every computer
+    /// has halted
+    pub fn run_until_event(&mut self) -> Result<NetworkEvent, IntcodeError> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Ok(event);
+            }
+
+            if self.nodes.iter().all(|node| node.halted) {
+                return Ok(NetworkEvent::Halted);
+            }
+
+            self.take_turn(self.next)?;
+            self.next += 1;
+
+            if self.next == self.nodes.len() {
+                self.next = 0;
+
+                let idle = self.nodes.iter().all(|node| node.halted || node.waiting)
+                    && !self.nodes.iter().all(|node| node.halted);
+                if idle && !self.active && self.events.is_empty() {
+                    self.events.push_back(NetworkEvent::Idle);
+                }
+                self.active = false;
+            }
+        }
+    }
+
+    fn take_turn(&mut self, address: usize) -> Result<(), IntcodeError> {
+        let node = &mut self.nodes[address];
+        if node.halted {
+            return Ok(());
+        }
+
+        let mut packets = Vec::new();
+        node.waiting = false;
+
+        for _ in 0..self.turn_length {
+            match node.computer.step()? {
+                None => {}
+                Some(IntcodeEvent::NeedsInput) => {
+                    node.computer.push_input(NO_PACKET);
+                    node.waiting = true;
+                    break;
+                }
+                Some(IntcodeEvent::Output(value)) => {
+                    node.output.push(value);
+                    if node.output.len() == 1 + self.packet_length {
+                        let mut values = node.output.split_off(0);
+                        let destination = values.remove(0);
+                        packets.push(Packet {
+                            source: address,
+                            destination,
+                            values,
+                        });
+                    }
+                }
+                Some(IntcodeEvent::Halted) => {
+                    node.halted = true;
+                    break;
+                }
+            }
+        }
+
+        for packet in packets {
+            match usize::try_from(packet.destination) {
+                Ok(destination) if destination < self.nodes.len() => {
+                    self.send(destination, &packet.values)
+                }
+                _ => {
+                    self.active = true;
+                    self.events.push_back(NetworkEvent::Packet(packet));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::year_2019::assemble;
+
+    /// Each computer adds one to the first value of every packet it receives, and sends it on to the next address.
+    /// Computer 0 starts by sending 100, 0 to computer 1.
+    const RELAY: &str = "
+                IN -> [address]
+                JNZ [address], #loop
+                OUT #1
+                OUT #100
+                OUT #0
+        loop:   IN -> [x]
+                EQ [x], #-1 -> [empty]
+                JNZ [empty], #loop
+                IN -> [y]
+                ADD [address], #1 -> [destination]
+                ADD [x], #1 -> [x]
+                OUT [destination]
+                OUT [x]
+                OUT [y]
+                JNZ #1, #loop
+        address:     DATA 0
+        x:           DATA 0
+        y:           DATA 0
+        destination: DATA 0
+        empty:       DATA 0";
+
+    fn packet(values: Vec<i64>) -> NetworkEvent {
+        NetworkEvent::Packet(Packet {
+            source: 2,
+            destination: 3,
+            values,
+        })
+    }
+
+    #[test]
+    fn test_network() {
+        let mut network = IntcodeNetwork::new(&assemble(RELAY).unwrap(), 3);
+
+        assert_eq!(network.run_until_event(), Ok(packet(vec![102, 0])));
+        assert_eq!(network.run_until_event(), Ok(NetworkEvent::Idle));
+
+        network.send(0, &[5, 7]);
+        assert_eq!(network.run_until_event(), Ok(packet(vec![8, 7])));
+        assert_eq!(network.run_until_event(), Ok(NetworkEvent::Idle));
+
+        network.broadcast(&[10, 0]);
+        let mut packets = Vec::new();
+        loop {
+            match network.run_until_event().unwrap() {
+                NetworkEvent::Packet(packet) => packets.push(packet.values[0]),
+                NetworkEvent::Idle => break,
+                NetworkEvent::Halted => panic!("the relay never halts"),
+            }
+        }
+        packets.sort_unstable();
+        assert_eq!(packets, vec![11, 12, 13]);
+    }
+
+    #[test]
+    fn test_halted_network() {
+        let program: IntcodeProgram = "3,0,99".parse().unwrap();
+        let mut network = IntcodeNetwork::new(&program, 4);
+
+        assert_eq!(network.run_until_event(), Ok(NetworkEvent::Halted));
+        assert_eq!(network.len(), 4);
+        assert_eq!(network.computer(3).memory.get(0), 3);
+    }
+}
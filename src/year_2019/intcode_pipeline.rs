@@ -0,0 +1,217 @@
+//! Intcode computers wired together in series, like the amplifiers of 2019 day 7.
+//!
+//! Each stage's output is sent as input to the next stage, and the output of the last stage is the output of the
+//! pipeline. Feedback edges send a stage's output to an earlier stage as well (most often the last stage back to the
+//! first), which keeps the pipeline running until every stage has halted or none of them can make progress.
+//!
+//! Each stage can be given initial input (like a phase setting) before the pipeline runs, and
+//! `max_over_permutations` searches every ordering of a set of settings for the best result, on all cores.
+use super::{IntcodeComputer, IntcodeError, IntcodeEvent, IntcodeProgram};
+use itertools::Itertools;
+use rayon::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Stage {
+    computer: IntcodeComputer,
+
+    /// The stages this stage's output is sent to
+    destinations: Vec<usize>,
+
+    halted: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct IntcodePipeline {
+    stages: Vec<Stage>,
+}
+
+impl IntcodePipeline {
+    /// A pipeline of `stages` computers all running `program`
+    pub fn new(program: &IntcodeProgram, stages: usize) -> Self {
+        Self::from_computers(
+            (0..stages)
+                .map(|_| IntcodeComputer::from(program))
+                .collect(),
+        )
+    }
+
+    /// A pipeline with a stage for each computer, in order
+    pub fn from_computers(computers: Vec<IntcodeComputer>) -> Self {
+        let count = computers.len();
+        let stages = computers
+            .into_iter()
+            .enumerate()
+            .map(|(index, computer)| Stage {
+                computer,
+                destinations: (index + 1..count).take(1).collect(),
+                halted: false,
+            })
+            .collect();
+
+        Self { stages }
+    }
+
+    /// Also sends the output of stage `from` to stage `to`
+    pub fn add_feedback(&mut self, from: usize, to: usize) {
+        for stage in &[from, to] {
+            assert!(
+                *stage < self.stages.len(),
+                "no stage {} in the pipeline",
+                stage
+            );
+        }
+
+        let destinations = &mut self.stages[from].destinations;
+        if !destinations.contains(&to) {
+            destinations.push(to);
+        }
+    }
+
+    /// Queues a value for stage `stage` to read before anything sent to it by other stages
+    pub fn push_input(&mut self, stage: usize, value: i64) {
+        self.stages[stage].computer.push_input(value);
+    }
+
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    pub fn computer(&self, stage: usize) -> &IntcodeComputer {
+        &self.stages[stage].computer
+    }
+
+    pub fn computer_mut(&mut self, stage: usize) -> &mut IntcodeComputer {
+        &mut self.stages[stage].computer
+    }
+
+    /// Sends `input` to the first stage, then runs the stages in turn until every stage has halted, or a whole round
+    /// passes without any of them producing output. Returns everything the last stage output.
+    pub fn run(&mut self, input: &[i64]) -> Result<Vec<i64>, IntcodeError> {
+        let mut output = Vec::new();
+        let last = match self.stages.len() {
+            0 => return Ok(output),
+            count => count - 1,
+        };
+
+        for value in input {
+            self.stages[0].computer.push_input(*value);
+        }
+
+        loop {
+            let mut progress = false;
+
+            for index in 0..self.stages.len() {
+                while !self.stages[index].halted {
+                    let value = match self.stages[index].computer.run_until_event()? {
+                        IntcodeEvent::Output(value) => value,
+                        IntcodeEvent::NeedsInput => break,
+                        IntcodeEvent::Halted => {
+                            self.stages[index].halted = true;
+                            break;
+                        }
+                    };
+
+                    progress = true;
+                    if index == last {
+                        output.push(value);
+                    }
+                    for destination in self.stages[index].destinations.clone() {
+                        self.stages[destination].computer.push_input(value);
+                    }
+                }
+            }
+
+            if !progress || self.stages.iter().all(|stage| stage.halted) {
+                return Ok(output);
+            }
+        }
+    }
+}
+
+/// Tries every ordering of `settings` in parallel, and returns the highest result of `f` with the ordering which
+/// produced it. Returns `None` if `f` returned `None` for every ordering.
+pub fn max_over_permutations<F>(
+    settings: &[i64],
+    f: F,
+) -> Result<Option<(i64, Vec<i64>)>, IntcodeError>
+where
+    F: Fn(&[i64]) -> Result<Option<i64>, IntcodeError> + Sync,
+{
+    let permutations: Vec<Vec<i64>> = settings
+        .iter()
+        .copied()
+        .permutations(settings.len())
+        .collect();
+
+    let results = permutations
+        .into_par_iter()
+        .map(|permutation| Ok(f(&permutation)?.map(|result| (result, permutation))))
+        .collect::<Result<Vec<_>, IntcodeError>>()?;
+
+    Ok(results.into_iter().flatten().max())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::year_2019::assemble;
+
+    /// Adds its first input to every input after it, and halts when it reads a zero
+    const ADDER: &str = "
+                IN -> [addend]
+        loop:   IN -> [x]
+                JZ [x], #end
+                ADD [x], [addend] -> [x]
+                OUT [x]
+                JNZ #1, #loop
+        end:    HALT
+        addend: DATA 0
+        x:      DATA 0";
+
+    #[test]
+    fn test_series() {
+        let mut pipeline = IntcodePipeline::new(&assemble(ADDER).unwrap(), 3);
+        for (stage, addend) in [1, 10, 100].iter().enumerate() {
+            pipeline.push_input(stage, *addend);
+        }
+
+        // only the first stage reads the zero, so the others are left waiting for more input
+        assert_eq!(pipeline.run(&[1, 2, 0]), Ok(vec![112, 113]));
+        assert!(pipeline.stages[0].halted);
+        assert!(!pipeline.stages[2].halted);
+    }
+
+    #[test]
+    fn test_feedback() {
+        let mut pipeline = IntcodePipeline::new(&assemble(ADDER).unwrap(), 2);
+        pipeline.push_input(0, 1);
+        pipeline.push_input(1, -10);
+        pipeline.add_feedback(1, 0);
+
+        // the signal goes around the loop losing 9 each time, until the first stage reads zero and halts
+        assert_eq!(
+            pipeline.run(&[99]),
+            Ok(vec![90, 81, 72, 63, 54, 45, 36, 27, 18, 9, 0])
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "no stage 2 in the pipeline")]
+    fn test_feedback_from_missing_stage() {
+        IntcodePipeline::new(&assemble(ADDER).unwrap(), 2).add_feedback(2, 0);
+    }
+
+    #[test]
+    fn test_max_over_permutations() {
+        let best = max_over_permutations(&[1, 2, 3], |settings| {
+            Ok(Some(settings[0] * 100 + settings[1] * 10 - settings[2]))
+        });
+        assert_eq!(best, Ok(Some((319, vec![3, 2, 1]))));
+
+        assert_eq!(max_over_permutations(&[1, 2], |_| Ok(None)), Ok(None));
+    }
+}
@@ -0,0 +1,235 @@
+//! Counting which instructions an `IntcodeComputer` executes.
+//!
+//! Once profiling is turned on with `IntcodeComputer::set_profiling`, every executed instruction is counted by address
+//! and by opcode. The report annotates a disassembly of the program with those counts:
+//!
+//! ```text
+//!    count  instruction
+//!        2  0000: IN -> [20]
+//!        2  0002: JZ [20], #14
+//!        1  0005: MUL #2, [20] -> [20]
+//!        1  0009: OUT [20]
+//!        1  0011: JNZ #1, #0
+//!        1  0014: HALT
+//! ```
+//!
+//! followed by the totals for each opcode, and the ranges of instructions which never ran.
+use super::intcode_disassembler::{disassemble_at, DisassembledLine, MNEMONICS};
+use super::IntcodeProgram;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::ops::Range;
+
+/// How many times each instruction was executed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntcodeProfile {
+    /// Keyed by address, as programs can jump anywhere in their (sparse) memory
+    counts: BTreeMap<usize, u64>,
+
+    /// Keyed by opcode
+    opcodes: BTreeMap<i64, u64>,
+}
+
+impl IntcodeProfile {
+    pub(super) fn record(&mut self, address: usize, opcode: i64) {
+        *self.counts.entry(address).or_insert(0) += 1;
+
+        *self.opcodes.entry(opcode).or_insert(0) += 1;
+    }
+
+    /// How many times the instruction at `address` was executed
+    pub fn count(&self, address: usize) -> u64 {
+        *self.counts.get(&address).unwrap_or(&0)
+    }
+
+    /// The addresses of executed instructions and how many times each ran, from the lowest address
+    pub fn counts(&self) -> impl Iterator<Item = (usize, u64)> + '_ {
+        self.counts
+            .iter()
+            .map(|(address, count)| (*address, *count))
+    }
+
+    /// How many instructions with each mnemonic were executed, in opcode order
+    pub fn opcode_totals(&self) -> Vec<(&'static str, u64)> {
+        self.opcodes
+            .iter()
+            .map(|(opcode, total)| {
+                let &(_, mnemonic, ..) = MNEMONICS
+                    .iter()
+                    .find(|(known, ..)| known == opcode)
+                    .expect("only valid opcodes are executed");
+                (mnemonic, *total)
+            })
+            .collect()
+    }
+
+    /// Disassembles `program`, following the addresses which were executed.
+    ///
+    /// The listing is a linear sweep like `intcode_disassembler::disassemble_lines`, except that executed addresses
+    /// always start a new line, so instructions hidden inside what looks like another instruction are still shown.
+    /// Instructions executed beyond the dense region of `program` follow, each on its own line.
+    pub fn listing(&self, program: &IntcodeProgram) -> Vec<DisassembledLine> {
+        let mut lines = Vec::new();
+        let mut address = 0;
+
+        while address < program.data().len() {
+            let mut line = disassemble_at(program, address);
+            let inner = address + 1..address.saturating_add(line.length);
+            if self.counts.range(inner).next().is_some() {
+                line = DisassembledLine {
+                    address,
+                    length: 1,
+                    text: format!("DATA {}", program.get(address)),
+                };
+            }
+
+            address += line.length;
+            lines.push(line);
+        }
+
+        lines.extend(
+            self.counts
+                .range(address..)
+                .map(|(address, _)| disassemble_at(program, *address)),
+        );
+
+        lines
+    }
+
+    /// The address ranges of consecutive instructions in `program` which were never executed
+    pub fn never_executed(&self, program: &IntcodeProgram) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+
+        for line in self.listing(program) {
+            if line.text.starts_with("DATA") || self.count(line.address) > 0 {
+                continue;
+            }
+
+            match ranges.last_mut() {
+                Some(range) if range.end == line.address => range.end += line.length,
+                _ => ranges.push(line.address..line.address + line.length),
+            }
+        }
+
+        ranges
+    }
+
+    /// The annotated disassembly of `program`, opcode totals and instructions which never ran
+    pub fn report(&self, program: &IntcodeProgram) -> String {
+        let mut report = format!("{:>8}  instruction\n", "count");
+        for line in self.listing(program) {
+            let count = match self.count(line.address) {
+                0 => "-".to_string(),
+                count => count.to_string(),
+            };
+            writeln!(report, "{:>8}  {}", count, line).unwrap();
+        }
+
+        report.push_str("\nopcode totals:\n");
+        for (mnemonic, total) in self.opcode_totals() {
+            writeln!(report, "{:>8}  {}", total, mnemonic).unwrap();
+        }
+
+        report.push_str("\nnever executed:\n");
+        let never_executed = self.never_executed(program);
+        if never_executed.is_empty() {
+            report.push_str("  nothing\n");
+        }
+        for range in never_executed {
+            writeln!(report, "  {:04}..{:04}", range.start, range.end).unwrap();
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::year_2019::{IntcodeComputer, IntcodeEvent};
+
+    #[test]
+    fn test_profile() {
+        // doubles each input until it reads a zero, with an unreachable output and some data at the end
+        let program: IntcodeProgram = "3,20,1006,20,14,102,2,20,20,4,20,1105,1,0,99,104,1,99,7,7"
+            .parse()
+            .unwrap();
+
+        let mut computer = IntcodeComputer::from(&program);
+        computer.set_profiling(true);
+        assert_eq!(computer.run_with_input(vec![1, 2, 0]).unwrap(), vec![2, 4]);
+
+        // running a halted program again doesn't count its halt again
+        assert_eq!(computer.run_until_event(), Ok(IntcodeEvent::Halted));
+
+        let profile = computer.profile().unwrap();
+        assert_eq!(
+            profile.counts().collect::<Vec<_>>(),
+            vec![(0, 3), (2, 3), (5, 2), (9, 2), (11, 2), (14, 1)]
+        );
+        assert_eq!(
+            profile.opcode_totals(),
+            vec![
+                ("MUL", 2),
+                ("IN", 3),
+                ("OUT", 2),
+                ("JNZ", 2),
+                ("JZ", 3),
+                ("HALT", 1)
+            ]
+        );
+        assert_eq!(profile.never_executed(&program), vec![15..18]);
+        assert_eq!(
+            profile.report(&program),
+            "   count  instruction\n\
+             \x20      3  0000: IN -> [20]\n\
+             \x20      3  0002: JZ [20], #14\n\
+             \x20      2  0005: MUL #2, [20] -> [20]\n\
+             \x20      2  0009: OUT [20]\n\
+             \x20      2  0011: JNZ #1, #0\n\
+             \x20      1  0014: HALT\n\
+             \x20      -  0015: OUT #1\n\
+             \x20      -  0017: HALT\n\
+             \x20      -  0018: DATA 7\n\
+             \x20      -  0019: DATA 7\n\
+             \n\
+             opcode totals:\n\
+             \x20      2  MUL\n\
+             \x20      3  IN\n\
+             \x20      2  OUT\n\
+             \x20      2  JNZ\n\
+             \x20      3  JZ\n\
+             \x20      1  HALT\n\
+             \n\
+             never executed:\n\
+             \x20 0015..0018\n"
+        );
+    }
+
+    #[test]
+    fn test_far_jump() {
+        // writes a HALT far beyond the end of the program, and jumps to it
+        let program: IntcodeProgram = "1101,99,0,1000000000000,1105,1,1000000000000"
+            .parse()
+            .unwrap();
+
+        let mut computer = IntcodeComputer::from(&program);
+        computer.set_profiling(true);
+        computer.run().unwrap();
+
+        let profile = computer.profile().unwrap();
+        assert_eq!(
+            profile.counts().collect::<Vec<_>>(),
+            vec![(0, 1), (4, 1), (1_000_000_000_000, 1)]
+        );
+        assert_eq!(profile.count(1_000_000_000_000), 1);
+        assert_eq!(
+            profile
+                .listing(&computer.memory)
+                .last()
+                .unwrap()
+                .to_string(),
+            "1000000000000: HALT"
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! Saving the state of an `IntcodeComputer` so it can be restored later.
+//!
+//! A snapshot holds everything needed to resume a program: its memory, instruction pointer, relative base and any
+//! input pushed but not yet read. The connected input, output and tracer aren't part of it.
+//!
+//! Snapshots can be stored as JSON text, or as bytes: the magic `ICS2`, then the instruction pointer, relative base,
+//! memory length, memory, pending input length, pending input, sparse memory length and sparse memory (as pairs of
+//! address and value), each as a little-endian 64-bit integer. Snapshots in the older `ICS1` format, which ends after
+//! the pending input, can still be read, and have no sparse memory.
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
+
+const MAGIC: &[u8] = b"ICS2";
+
+/// The magic of snapshots saved before memory could be sparse
+const MAGIC_V1: &[u8] = b"ICS1";
+
+/// The state of an `IntcodeComputer` at some point during its run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntcodeSnapshot {
+    /// The dense region of memory
+    pub memory: Vec<i64>,
+
+    /// The addresses and values of memory stored sparsely, beyond the dense region
+    #[serde(default)]
+    pub sparse_memory: Vec<(usize, i64)>,
+
+    pub instruction_pointer: usize,
+    pub relative_base: i64,
+    pub pending_input: Vec<i64>,
+}
+
+impl IntcodeSnapshot {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("IntcodeSnapshot always serializes")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|error| Error::Snapshot(error.to_string()))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            MAGIC.len()
+                + 8 * (5
+                    + self.memory.len()
+                    + self.pending_input.len()
+                    + 2 * self.sparse_memory.len()),
+        );
+        bytes.extend(MAGIC);
+        bytes.extend(&(self.instruction_pointer as u64).to_le_bytes());
+        bytes.extend(&self.relative_base.to_le_bytes());
+        for values in &[&self.memory, &self.pending_input] {
+            bytes.extend(&(values.len() as u64).to_le_bytes());
+            for value in values.iter() {
+                bytes.extend(&value.to_le_bytes());
+            }
+        }
+        bytes.extend(&(self.sparse_memory.len() as u64).to_le_bytes());
+        for (address, value) in &self.sparse_memory {
+            bytes.extend(&(*address as u64).to_le_bytes());
+            bytes.extend(&value.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = ByteReader(bytes);
+
+        let has_sparse_memory = match reader.take(MAGIC.len())? {
+            MAGIC => true,
+            MAGIC_V1 => false,
+            _ => return Err(Error::Snapshot("not an Intcode snapshot".into())),
+        };
+
+        let instruction_pointer = reader.usize()?;
+        let relative_base = reader.i64()?;
+        let memory = reader.values()?;
+        let pending_input = reader.values()?;
+        let sparse_memory = if has_sparse_memory {
+            (0..reader.length(16)?)
+                .map(|_| Ok((reader.usize()?, reader.i64()?)))
+                .collect::<Result<_>>()?
+        } else {
+            Vec::new()
+        };
+
+        if !reader.0.is_empty() {
+            return Err(Error::Snapshot(format!(
+                "{} unexpected bytes at the end",
+                reader.0.len()
+            )));
+        }
+
+        Ok(Self {
+            memory,
+            sparse_memory,
+            instruction_pointer,
+            relative_base,
+            pending_input,
+        })
+    }
+}
+
+/// Reads the fields of a snapshot from the front of a byte slice
+struct ByteReader<'a>(&'a [u8]);
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8]> {
+        if self.0.len() < length {
+            return Err(Error::Snapshot("unexpected end of snapshot".into()));
+        }
+
+        let (taken, rest) = self.0.split_at(length);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn i64(&mut self) -> Result<i64> {
+        let bytes = self.take(8)?;
+        Ok(i64::from_le_bytes(bytes.try_into().expect("took 8 bytes")))
+    }
+
+    fn usize(&mut self) -> Result<usize> {
+        let bytes = self.take(8)?;
+        u64::from_le_bytes(bytes.try_into().expect("took 8 bytes"))
+            .try_into()
+            .map_err(|_| Error::Snapshot("length or address out of range".into()))
+    }
+
+    /// The length of a list of items `item_size` bytes long, checking they're all there
+    fn length(&mut self, item_size: usize) -> Result<usize> {
+        let length = self.usize()?;
+        if self.0.len() / item_size < length {
+            return Err(Error::Snapshot("unexpected end of snapshot".into()));
+        }
+
+        Ok(length)
+    }
+
+    /// A length, followed by that many values
+    fn values(&mut self) -> Result<Vec<i64>> {
+        (0..self.length(8)?).map(|_| self.i64()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::year_2019::{IntcodeComputer, IntcodeEvent};
+
+    /// Outputs the running total of its inputs, until it reads a zero
+    const RUNNING_TOTAL: &str = "3,20,1005,20,6,99,1,20,21,21,4,21,1105,1,0";
+
+    #[test]
+    fn test_snapshot_and_restore() {
+        let mut computer: IntcodeComputer = RUNNING_TOTAL.parse().unwrap();
+        computer.push_input(1);
+        computer.push_input(2);
+        assert_eq!(computer.run_until_event(), Ok(IntcodeEvent::Output(1)));
+
+        let snapshot = computer.snapshot();
+        assert_eq!(snapshot.instruction_pointer, 12);
+        assert_eq!(snapshot.pending_input, vec![2]);
+
+        for restored in &[
+            IntcodeSnapshot::from_json(&snapshot.to_json()).unwrap(),
+            IntcodeSnapshot::from_bytes(&snapshot.to_bytes()).unwrap(),
+        ] {
+            assert_eq!(*restored, snapshot);
+
+            let mut restored = IntcodeComputer::from(restored);
+            assert_eq!(restored.run_with_input(vec![40, 0]).unwrap(), vec![3, 43]);
+        }
+
+        assert_eq!(computer.run_with_input(vec![5, 0]).unwrap(), vec![3, 8]);
+        computer.restore(&snapshot);
+        assert_eq!(computer.run_with_input(vec![6, 0]).unwrap(), vec![3, 9]);
+
+        assert!(IntcodeSnapshot::from_bytes(b"ICS0").is_err());
+        assert!(IntcodeSnapshot::from_bytes(&snapshot.to_bytes()[..40]).is_err());
+        assert!(IntcodeSnapshot::from_json("{\"memory\": []}").is_err());
+    }
+
+    #[test]
+    fn test_version_1() {
+        let mut bytes = b"ICS1".to_vec();
+        for value in &[0, 0, 4, 104, 7, 99, 0, 1, 5] {
+            bytes.extend(&(*value as i64).to_le_bytes());
+        }
+
+        let snapshot = IntcodeSnapshot::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            snapshot,
+            IntcodeSnapshot {
+                memory: vec![104, 7, 99, 0],
+                sparse_memory: vec![],
+                instruction_pointer: 0,
+                relative_base: 0,
+                pending_input: vec![5],
+            }
+        );
+        assert_eq!(&snapshot.to_bytes()[..4], b"ICS2");
+
+        bytes.extend(&0_i64.to_le_bytes());
+        assert!(IntcodeSnapshot::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut computer: IntcodeComputer = RUNNING_TOTAL.parse().unwrap();
+        computer.push_input(1);
+        assert_eq!(computer.run_until_event(), Ok(IntcodeEvent::Output(1)));
+        assert_eq!(computer.run_until_event(), Ok(IntcodeEvent::NeedsInput));
+
+        let mut branch = computer.clone();
+        assert_eq!(computer.run_with_input(vec![2, 0]).unwrap(), vec![3]);
+        assert_eq!(branch.run_with_input(vec![10, 0]).unwrap(), vec![11]);
+    }
+}
@@ -0,0 +1,192 @@
+//! Records every instruction an `IntcodeComputer` executes, one line per instruction.
+//!
+//! Each line holds four fields separated by ` | `: the address of the instruction and the relative base before it
+//! ran, the instruction itself (as the disassembler prints it), the values it read from memory, and what it did:
+//!
+//! ```text
+//! 0000 rb=0 | IN -> [20] | - | [20]<-21
+//! 0002 rb=0 | JZ [20], #14 | [20]=21 | -
+//! 0005 rb=0 | MUL #2, [20] -> [20] | [20]=21 | [20]<-42
+//! 0009 rb=0 | OUT [20] | [20]=42 | out 42
+//! 0011 rb=0 | JNZ #1, #0 | - | ip<-0
+//! 0014 rb=0 | HALT | - | halt
+//! ```
+//!
+//! Reads and writes through relative parameters are shown at the address they resolved to. Two runs of the same
+//! program with the same input produce identical traces, so a plain `diff` finds the first instruction where two
+//! interpreters disagree.
+use super::intcode_computer::{IntcodeInstruction, IntcodeParameter};
+use super::intcode_disassembler::{instruction_text, MNEMONICS};
+use super::{IntcodeComputer, IntcodeError};
+use std::io::{self, BufWriter, Write};
+
+/// Where a traced `IntcodeComputer` writes its trace
+pub(super) struct IntcodeTracer {
+    writer: BufWriter<Box<dyn Write>>,
+}
+
+impl IntcodeTracer {
+    pub fn new(writer: impl Write + 'static) -> Self {
+        Self {
+            writer: BufWriter::new(Box::new(writer)),
+        }
+    }
+
+    pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+        writeln!(self.writer, "{}", line)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// What an instruction looked like before it executed
+pub(super) struct TraceEntry {
+    address: usize,
+    relative_base: i64,
+    length: usize,
+    text: String,
+    halts: bool,
+    reads: Vec<(usize, i64)>,
+    output: Option<i64>,
+    destination: Option<usize>,
+}
+
+impl TraceEntry {
+    /// Decodes the next instruction of `computer`, and resolves the parameters it will read and write
+    pub fn new(computer: &IntcodeComputer) -> Result<Self, IntcodeError> {
+        let address = computer.instruction_pointer();
+        let instruction = IntcodeInstruction::decode(&computer.memory, address)?;
+        let &(_, _, _, writes) = MNEMONICS
+            .iter()
+            .find(|(opcode, ..)| *opcode == instruction.opcode())
+            .expect("every opcode has a mnemonic");
+
+        let mut parameters = instruction.parameters();
+        let destination = match parameters.pop() {
+            Some(parameter) if writes => parameter.get_address(computer).ok(),
+            Some(parameter) => {
+                parameters.push(parameter);
+                None
+            }
+            None => None,
+        };
+
+        let reads = parameters
+            .iter()
+            .filter(|parameter| !matches!(parameter, IntcodeParameter::Value(_)))
+            .filter_map(|parameter| parameter.get_address(computer).ok())
+            .map(|address| (address, computer.memory.get(address)))
+            .collect();
+        let output = match &instruction {
+            IntcodeInstruction::Output(parameter) => parameter.get_value(computer).ok(),
+            _ => None,
+        };
+
+        Ok(Self {
+            address,
+            relative_base: computer.relative_base(),
+            length: instruction.length(),
+            text: instruction_text(&instruction),
+            halts: matches!(instruction, IntcodeInstruction::Halt),
+            reads,
+            output,
+            destination,
+        })
+    }
+
+    /// The address of the traced instruction
+    pub fn address(&self) -> usize {
+        self.address
+    }
+
+    /// Formats the trace line, once `computer` has executed the instruction
+    pub fn finish(&self, computer: &IntcodeComputer) -> String {
+        let mut effects = Vec::new();
+        if let Some(address) = self.destination {
+            effects.push(format!("[{}]<-{}", address, computer.memory.get(address)));
+        }
+        if let Some(value) = self.output {
+            effects.push(format!("out {}", value));
+        }
+        if self.halts {
+            effects.push("halt".to_string());
+        } else if computer.instruction_pointer() != self.address + self.length {
+            effects.push(format!("ip<-{}", computer.instruction_pointer()));
+        }
+        if computer.relative_base() != self.relative_base {
+            effects.push(format!("rb<-{}", computer.relative_base()));
+        }
+
+        let reads: Vec<String> = self
+            .reads
+            .iter()
+            .map(|(address, value)| format!("[{}]={}", address, value))
+            .collect();
+
+        format!(
+            "{:04} rb={} | {} | {} | {}",
+            self.address,
+            self.relative_base,
+            self.text,
+            field(reads),
+            field(effects)
+        )
+    }
+}
+
+fn field(items: Vec<String>) -> String {
+    if items.is_empty() {
+        "-".to_string()
+    } else {
+        items.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::year_2019::IntcodeComputer;
+    use std::cell::RefCell;
+    use std::io::{self, Write};
+    use std::rc::Rc;
+
+    /// A writer which can be read back after the computer that owns it is done
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_trace() {
+        let trace = SharedBuffer::default();
+
+        let mut computer: IntcodeComputer = "109,5,3,20,1006,20,16,102,2,20,20,204,15,1105,1,2,99"
+            .parse()
+            .unwrap();
+        computer.set_tracer(trace.clone());
+        assert_eq!(computer.run_with_input(vec![21, 0]).unwrap(), vec![42]);
+        drop(computer);
+
+        assert_eq!(
+            String::from_utf8(trace.0.take()).unwrap(),
+            "0000 rb=0 | ARB #5 | - | rb<-5\n\
+             0002 rb=5 | IN -> [20] | - | [20]<-21\n\
+             0004 rb=5 | JZ [20], #16 | [20]=21 | -\n\
+             0007 rb=5 | MUL #2, [20] -> [20] | [20]=21 | [20]<-42\n\
+             0011 rb=5 | OUT [rb+15] | [20]=42 | out 42\n\
+             0013 rb=5 | JNZ #1, #2 | - | ip<-2\n\
+             0002 rb=5 | IN -> [20] | - | [20]<-0\n\
+             0004 rb=5 | JZ [20], #16 | [20]=0 | ip<-16\n\
+             0016 rb=5 | HALT | - | halt\n"
+        );
+    }
+}